@@ -1,18 +1,14 @@
 //! Basic usage example for StateSet SDK
 
-use stateset::{Client, auth::Credentials, Result};
+use stateset::{Client, Config, ReferenceType, Result};
 use stateset::models::order::{CreateOrderRequest, OrderStatus};
-use stateset::models::inventory::ReferenceType;
 use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize client
-    let token = std::env::var("STATESET_TOKEN")
-        .unwrap_or_else(|_| "demo-token".to_string());
-    
-    let client = Client::new("https://api.stateset.com")?
-        .authenticate(Credentials::bearer(token))?;
+    // Initialize the client from STATESET_CONFIG / STATESET_PROFILE and
+    // STATESET_* variables, e.g. STATESET_BASE_URL and STATESET_TOKEN
+    let client = Client::with_config(Config::load()?)?;
 
     println!("StateSet SDK Example");
     println!("====================\n");
@@ -49,7 +45,7 @@ async fn main() -> Result<()> {
         .inventory()
         .reserve()
         .warehouse("WH001")
-        .reference(order_id, ReferenceType::SalesOrder(order_id.into()))
+        .reference(order_id, ReferenceType::Custom("sales_order".to_string()))
         .item(product1, 2)
         .item(product2, 1);
 
@@ -295,6 +295,7 @@ fn error_type_description(error: &stateset_core::Error) -> &'static str {
         stateset_core::Error::ConnectionPool { .. } => "Connection Pool Error",
         stateset_core::Error::Conflict { .. } => "Resource Conflict",
         stateset_core::Error::ServiceUnavailable { .. } => "Service Unavailable",
+        stateset_core::Error::CircuitOpen { .. } => "Circuit Open",
         stateset_core::Error::InvalidRequest { .. } => "Invalid Request",
         stateset_core::Error::QuotaExceeded { .. } => "Quota Exceeded",
         stateset_core::Error::Other(_) => "Other Error",
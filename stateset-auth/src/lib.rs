@@ -56,6 +56,18 @@ impl Credentials {
     }
 }
 
+impl From<stateset_core::CredentialSettings> for Credentials {
+    fn from(settings: stateset_core::CredentialSettings) -> Self {
+        match settings {
+            stateset_core::CredentialSettings::Bearer(token) => Self::Bearer(token),
+            stateset_core::CredentialSettings::ApiKey(key) => Self::ApiKey(key),
+            stateset_core::CredentialSettings::OAuth2 { client_id, client_secret } => {
+                Self::oauth2(client_id, client_secret)
+            }
+        }
+    }
+}
+
 /// OAuth2 token response
 #[derive(Debug, Clone, Deserialize)]
 pub struct TokenResponse {
@@ -0,0 +1,767 @@
+//! Synchronous client for non-async code
+//!
+//! [`Client`] wraps the async [`crate::Client`] together with a private
+//! single-worker Tokio runtime and blocks the calling thread until each
+//! request completes. Every resource client, list builder and pagination
+//! stream has a blocking counterpart with the same methods, returning the
+//! same [`stateset_core::Error`] types; paginated streams become ordinary
+//! [`Iterator`]s.
+//!
+//! ```no_run
+//! use stateset_client::blocking::Client;
+//! use stateset_models::order::OrderStatus;
+//!
+//! # fn run() -> stateset_core::Result<()> {
+//! let client = Client::new("https://api.stateset.io")?;
+//!
+//! for order in client.orders().list().status(OrderStatus::Pending).iter() {
+//!     println!("{}", order?.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Panics
+//!
+//! Blocking calls must not be made from within an async runtime; doing so
+//! panics. Use the async client there, or move the work to a dedicated
+//! thread (e.g. `tokio::task::spawn_blocking`).
+
+use crate::cache::{CacheStats, ResponseCache};
+use crate::coalesce::{CoalescingStats, RequestCoalescer};
+use crate::compression::CompressionStats;
+use crate::concurrency::ConcurrencyStats;
+use crate::download::DownloadProgress;
+use crate::hedge::HedgePolicy;
+use crate::middleware::{RequestMiddleware, ResponseMiddleware};
+use crate::request::RequestOptions;
+use crate::response::ApiResponse;
+use crate::retry::RetryPolicy;
+use crate::throttle::RateLimitStatus;
+use crate::upload::MultipartUpload;
+use bytes::Bytes;
+use futures::io::AllowStdIo;
+use futures::stream::{Stream, StreamExt};
+use reqwest::{header::HeaderMap, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use stateset_auth::Credentials;
+use stateset_core::{Config, Error, Result};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Generate blocking wrappers for `&self` async methods of `self.inner`
+macro_rules! blocking_methods {
+    ($($(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)* $(,)?) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.runtime.block_on(self.inner.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// Generate blocking wrappers for consuming async methods of `self.inner`
+macro_rules! consuming_methods {
+    ($($(#[$meta:meta])* fn $name:ident(self) -> $ret:ty;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(self) -> $ret {
+                self.runtime.block_on(self.inner.$name())
+            }
+        )*
+    };
+}
+
+/// Generate pass-through setters for builders wrapping `self.inner`
+macro_rules! builder_setters {
+    ($($(#[$meta:meta])* fn $name:ident($($arg:ident: $ty:ty),* $(,)?);)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(self $(, $arg: $ty)*) -> Self {
+                Self {
+                    inner: self.inner.$name($($arg),*),
+                    runtime: self.runtime,
+                }
+            }
+        )*
+    };
+}
+
+/// Declare a blocking resource client wrapping its async counterpart
+macro_rules! blocking_resource {
+    ($(#[$meta:meta])* $name:ident => $inner:ty) => {
+        $(#[$meta])*
+        pub struct $name {
+            inner: $inner,
+            runtime: std::sync::Arc<$crate::blocking::Runtime>,
+        }
+
+        impl $name {
+            pub(crate) fn new(inner: $inner, runtime: std::sync::Arc<$crate::blocking::Runtime>) -> Self {
+                Self { inner, runtime }
+            }
+
+            /// Apply per-call overrides to every request made through this client
+            pub fn with_options(&self, options: $crate::request::RequestOptions) -> Self {
+                Self::new(self.inner.with_options(options), self.runtime.clone())
+            }
+
+            /// The underlying async client
+            pub fn as_async(&self) -> &$inner {
+                &self.inner
+            }
+        }
+    };
+}
+
+mod resources;
+
+pub use resources::{
+    analytics::{AnalyticsClient, AnalyticsListBuilder},
+    boms::{BomListBuilder, BomsClient},
+    carts::{CartListBuilder, CartsClient},
+    checkouts::{CheckoutListBuilder, CheckoutsClient},
+    inventory::{InventoryClient, InventoryReservationBuilder},
+    orders::{OrderListBuilder, OrderUpdateBuilder, OrdersClient},
+    products::{ProductListBuilder, ProductsClient},
+    returns::ReturnsClient,
+    shipments::ShipmentsClient,
+    warranties::{WarrantiesClient, WarrantyClaimListBuilder, WarrantyListBuilder},
+    work_orders::{WorkOrderListBuilder, WorkOrdersClient},
+};
+
+/// Runtime driving the async client behind the blocking API
+///
+/// A single worker thread keeps connection pool tasks running between calls.
+#[derive(Debug)]
+pub(crate) struct Runtime {
+    runtime: Option<tokio::runtime::Runtime>,
+}
+
+impl Runtime {
+    fn new() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("stateset-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to start blocking runtime: {}", e)))?;
+
+        Ok(Self {
+            runtime: Some(runtime),
+        })
+    }
+
+    /// Run a future to completion on the calling thread
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime
+            .as_ref()
+            .expect("blocking runtime is only taken on drop")
+            .block_on(future)
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        // Dropping a runtime inside another runtime panics; shutting down in
+        // the background is safe anywhere.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Synchronous StateSet client
+///
+/// Cloning is cheap; clones share the connection pool and runtime.
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("base_url", &self.inner.config().base_url.as_str())
+            .field("authenticated", &self.inner.is_authenticated())
+            .finish()
+    }
+}
+
+impl Client {
+    /// Create a new client with the default configuration
+    pub fn new(base_url: impl AsRef<str>) -> Result<Self> {
+        Self::from_async(crate::Client::new(base_url)?)
+    }
+
+    /// Create a new client with a custom configuration
+    pub fn with_config(config: Config) -> Result<Self> {
+        Self::from_async(crate::Client::with_config(config)?)
+    }
+
+    /// Drive an existing async client synchronously
+    ///
+    /// Use this to reuse a client set up with a custom transport or
+    /// middleware.
+    pub fn from_async(client: crate::Client) -> Result<Self> {
+        Ok(Self {
+            inner: client,
+            runtime: Arc::new(Runtime::new()?),
+        })
+    }
+
+    /// The underlying async client
+    pub fn as_async(&self) -> &crate::Client {
+        &self.inner
+    }
+
+    fn wrap(&self, inner: crate::Client) -> Self {
+        Self {
+            inner,
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Authenticate the client with credentials
+    pub fn authenticate(&self, credentials: Credentials) -> Self {
+        self.wrap(self.inner.authenticate(credentials))
+    }
+
+    /// Register a middleware that hooks both requests and responses
+    pub fn with_middleware<M>(&self, middleware: M) -> Self
+    where
+        M: RequestMiddleware + ResponseMiddleware + 'static,
+    {
+        self.wrap(self.inner.with_middleware(middleware))
+    }
+
+    /// Register a request-only middleware at the end of the chain
+    pub fn with_request_middleware(&self, middleware: impl RequestMiddleware + 'static) -> Self {
+        self.wrap(self.inner.with_request_middleware(middleware))
+    }
+
+    /// Register a response-only middleware at the end of the chain
+    pub fn with_response_middleware(&self, middleware: impl ResponseMiddleware + 'static) -> Self {
+        self.wrap(self.inner.with_response_middleware(middleware))
+    }
+
+    /// Apply per-call overrides to every request made through the returned client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        self.wrap(self.inner.with_options(options))
+    }
+
+    /// Cache `GET` responses and revalidate them with conditional requests
+    pub fn with_cache(&self, cache: ResponseCache) -> Self {
+        self.wrap(self.inner.with_cache(cache))
+    }
+
+    /// Share in-flight `GET`s between callers on different threads
+    pub fn with_coalescing(&self, coalescer: RequestCoalescer) -> Self {
+        self.wrap(self.inner.with_coalescing(coalescer))
+    }
+
+    /// Record call counts, latencies, errors and retries in `metrics`
+    pub fn with_metrics(&self, metrics: crate::metrics::Metrics) -> Self {
+        self.wrap(self.inner.with_metrics(metrics))
+    }
+
+    /// Hedge safe requests of the returned client with `policy`
+    pub fn with_hedging(&self, policy: HedgePolicy) -> Self {
+        self.wrap(self.inner.with_hedging(policy))
+    }
+
+    /// Replace the retry policy built from the configuration
+    pub fn with_retry_policy(&self, policy: RetryPolicy) -> Self {
+        self.wrap(self.inner.with_retry_policy(policy))
+    }
+
+    /// Request options applied to every request of this client
+    pub fn options(&self) -> &RequestOptions {
+        self.inner.options()
+    }
+
+    /// Get the current configuration
+    pub fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    /// Check if the client is authenticated
+    pub fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated()
+    }
+
+    /// Latest rate limit quota reported by the server
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+
+    /// Queue depth and wait times of the concurrency limit
+    pub fn concurrency_stats(&self) -> ConcurrencyStats {
+        self.inner.concurrency_stats()
+    }
+
+    /// Health of the base URL and each failover endpoint, in failover order
+    pub fn endpoint_health(&self) -> Vec<crate::failover::EndpointHealth> {
+        self.inner.endpoint_health()
+    }
+
+    /// State of every circuit breaker used so far
+    pub fn circuit_breaker_states(
+        &self,
+    ) -> std::collections::BTreeMap<String, crate::circuit_breaker::CircuitState> {
+        self.inner.circuit_breaker_states()
+    }
+
+    /// Call `callback` whenever a circuit breaker changes state
+    pub fn on_circuit_state_change<F>(&self, callback: F)
+    where
+        F: Fn(&crate::circuit_breaker::StateChange) + Send + Sync + 'static,
+    {
+        self.inner.on_circuit_state_change(callback)
+    }
+
+    /// Statistics of the response cache, if one is attached
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+
+    /// Statistics of request coalescing, if enabled
+    pub fn coalescing_stats(&self) -> Option<CoalescingStats> {
+        self.inner.coalescing_stats()
+    }
+
+    /// Bytes saved by request body compression, shared by all clones
+    pub fn compression_stats(&self) -> CompressionStats {
+        self.inner.compression_stats()
+    }
+
+    /// Snapshot of the metrics registry, if one is attached
+    pub fn metrics(&self) -> Option<crate::metrics::MetricsSnapshot> {
+        self.inner.metrics()
+    }
+
+    /// GET request
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.runtime.block_on(self.inner.get(path))
+    }
+
+    /// GET request with query parameters
+    pub fn get_with_query<T, Q>(&self, path: &str, query: &Q) -> Result<T>
+    where
+        T: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.get_with_query(path, query))
+    }
+
+    /// POST request
+    pub fn post<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.post(path, body))
+    }
+
+    /// POST request with a caller-supplied idempotency key
+    pub fn post_with_idempotency_key<T, B>(&self, path: &str, body: &B, key: impl AsRef<str>) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime
+            .block_on(self.inner.post_with_idempotency_key(path, body, key))
+    }
+
+    /// PUT request
+    pub fn put<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.put(path, body))
+    }
+
+    /// PATCH request
+    pub fn patch<T, B>(&self, path: &str, body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.patch(path, body))
+    }
+
+    /// PATCH request with a caller-supplied idempotency key
+    pub fn patch_with_idempotency_key<T, B>(&self, path: &str, body: &B, key: impl AsRef<str>) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime
+            .block_on(self.inner.patch_with_idempotency_key(path, body, key))
+    }
+
+    /// DELETE request
+    pub fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.runtime.block_on(self.inner.delete(path))
+    }
+
+    /// DELETE request without response body
+    pub fn delete_no_content(&self, path: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.delete_no_content(path))
+    }
+
+    /// Download a resource; the body is read on demand
+    pub fn download(&self, path: &str) -> Result<Download> {
+        let download = self.runtime.block_on(self.inner.download(path))?;
+        Ok(Download::new(download, self.runtime.clone()))
+    }
+
+    /// Download a resource starting at byte `offset`
+    pub fn download_range(&self, path: &str, offset: u64) -> Result<Download> {
+        let download = self.runtime.block_on(self.inner.download_range(path, offset))?;
+        Ok(Download::new(download, self.runtime.clone()))
+    }
+
+    /// Download a resource into `writer`, resuming after interruptions
+    ///
+    /// Returns the number of bytes written.
+    pub fn download_to<W, F>(&self, path: &str, writer: &mut W, progress: F) -> Result<u64>
+    where
+        W: std::io::Write + ?Sized,
+        F: FnMut(DownloadProgress),
+    {
+        let mut writer = AllowStdIo::new(writer);
+        self.runtime
+            .block_on(self.inner.download_to(path, &mut writer, progress))
+    }
+
+    /// Upload files as a `multipart/form-data` POST
+    pub fn upload<T: DeserializeOwned>(&self, path: &str, upload: impl Into<MultipartUpload>) -> Result<T> {
+        self.runtime.block_on(self.inner.upload(path, upload))
+    }
+
+    /// GET request returning the body together with response metadata
+    pub fn get_with_response<T: DeserializeOwned>(&self, path: &str) -> Result<ApiResponse<T>> {
+        self.runtime.block_on(self.inner.get_with_response(path))
+    }
+
+    /// GET request with query parameters returning response metadata
+    pub fn get_with_query_and_response<T, Q>(&self, path: &str, query: &Q) -> Result<ApiResponse<T>>
+    where
+        T: DeserializeOwned,
+        Q: Serialize + ?Sized,
+    {
+        self.runtime
+            .block_on(self.inner.get_with_query_and_response(path, query))
+    }
+
+    /// POST request returning the body together with response metadata
+    pub fn post_with_response<T, B>(&self, path: &str, body: &B) -> Result<ApiResponse<T>>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.post_with_response(path, body))
+    }
+
+    /// PUT request returning the body together with response metadata
+    pub fn put_with_response<T, B>(&self, path: &str, body: &B) -> Result<ApiResponse<T>>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.put_with_response(path, body))
+    }
+
+    /// PATCH request returning the body together with response metadata
+    pub fn patch_with_response<T, B>(&self, path: &str, body: &B) -> Result<ApiResponse<T>>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        self.runtime.block_on(self.inner.patch_with_response(path, body))
+    }
+
+    /// DELETE request returning response metadata
+    pub fn delete_with_response<T: DeserializeOwned>(&self, path: &str) -> Result<ApiResponse<T>> {
+        self.runtime.block_on(self.inner.delete_with_response(path))
+    }
+
+    /// Iterate over a paginated endpoint, fetching pages as needed
+    pub fn iter<T>(&self, path: &str) -> Iter<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        Iter::new(self.inner.stream(path), self.runtime.clone())
+    }
+
+    /// Iterate over a paginated endpoint with custom query parameters
+    pub fn iter_with_query<T, Q>(&self, path: &str, query: &Q) -> Iter<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+        Q: Serialize + Clone + Send + Sync + 'static,
+    {
+        Iter::new(self.inner.stream_with_query(path, query), self.runtime.clone())
+    }
+}
+
+// Resource accessors
+impl Client {
+    /// Access the Orders API
+    pub fn orders(&self) -> OrdersClient {
+        OrdersClient::new(self.inner.orders(), self.runtime.clone())
+    }
+
+    /// Access the Inventory API
+    pub fn inventory(&self) -> InventoryClient {
+        InventoryClient::new(self.inner.inventory(), self.runtime.clone())
+    }
+
+    /// Access the Returns API
+    pub fn returns(&self) -> ReturnsClient {
+        ReturnsClient::new(self.inner.returns(), self.runtime.clone())
+    }
+
+    /// Access the Shipments API
+    pub fn shipments(&self) -> ShipmentsClient {
+        ShipmentsClient::new(self.inner.shipments(), self.runtime.clone())
+    }
+
+    /// Access the Work Orders API
+    pub fn work_orders(&self) -> WorkOrdersClient {
+        WorkOrdersClient::new(self.inner.work_orders(), self.runtime.clone())
+    }
+
+    /// Access the Warranties API
+    pub fn warranties(&self) -> WarrantiesClient {
+        WarrantiesClient::new(self.inner.warranties(), self.runtime.clone())
+    }
+
+    /// Access the Bill of Materials API
+    pub fn boms(&self) -> BomsClient {
+        BomsClient::new(self.inner.boms(), self.runtime.clone())
+    }
+
+    /// Access the Products API
+    pub fn products(&self) -> ProductsClient {
+        ProductsClient::new(self.inner.products(), self.runtime.clone())
+    }
+
+    /// Access the Carts API
+    pub fn carts(&self) -> CartsClient {
+        CartsClient::new(self.inner.carts(), self.runtime.clone())
+    }
+
+    /// Access the Checkouts API
+    pub fn checkouts(&self) -> CheckoutsClient {
+        CheckoutsClient::new(self.inner.checkouts(), self.runtime.clone())
+    }
+
+    /// Access the Analytics API
+    pub fn analytics(&self) -> AnalyticsClient {
+        AnalyticsClient::new(self.inner.analytics(), self.runtime.clone())
+    }
+}
+
+/// Blocking iterator over a paginated endpoint
+///
+/// Pages are fetched lazily as the iterator advances. Errors are yielded as
+/// items; iteration ends after an error.
+pub struct Iter<T> {
+    stream: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+    runtime: Arc<Runtime>,
+}
+
+impl<T> std::fmt::Debug for Iter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Iter").finish_non_exhaustive()
+    }
+}
+
+impl<T> Iter<T> {
+    pub(crate) fn new(stream: impl Stream<Item = Result<T>> + Send + 'static, runtime: Arc<Runtime>) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            runtime,
+        }
+    }
+}
+
+impl<T> Iterator for Iter<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+/// A blocking download whose body is read on demand
+pub struct Download {
+    inner: crate::download::Download,
+    runtime: Arc<Runtime>,
+}
+
+impl std::fmt::Debug for Download {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl Download {
+    pub(crate) fn new(inner: crate::download::Download, runtime: Arc<Runtime>) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// HTTP status code
+    pub fn status(&self) -> StatusCode {
+        self.inner.status()
+    }
+
+    /// Response headers
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+
+    /// MIME type of the content, without parameters
+    pub fn content_type(&self) -> Option<&str> {
+        self.inner.content_type()
+    }
+
+    /// Length of this response body in bytes, if known
+    pub fn content_length(&self) -> Option<u64> {
+        self.inner.content_length()
+    }
+
+    /// Size of the complete content, accounting for partial responses
+    pub fn total_size(&self) -> Option<u64> {
+        self.inner.total_size()
+    }
+
+    /// File name suggested by the `Content-Disposition` header
+    pub fn filename(&self) -> Option<String> {
+        self.inner.filename()
+    }
+
+    /// Byte offset of the first chunk within the complete content
+    pub fn offset(&self) -> u64 {
+        self.inner.offset()
+    }
+
+    /// Whether this is a partial (range) response
+    pub fn is_partial(&self) -> bool {
+        self.inner.is_partial()
+    }
+
+    /// Buffer the whole body in memory
+    pub fn bytes(self) -> Result<Bytes> {
+        self.runtime.block_on(self.inner.bytes())
+    }
+
+    /// Write the body to `writer`, reporting progress after every chunk
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_to<W, F>(self, writer: &mut W, progress: F) -> Result<u64>
+    where
+        W: std::io::Write + ?Sized,
+        F: FnMut(DownloadProgress),
+    {
+        let mut writer = AllowStdIo::new(writer);
+        self.runtime.block_on(self.inner.write_to(&mut writer, progress))
+    }
+
+    /// The underlying async download
+    pub fn into_async(self) -> crate::download::Download {
+        self.inner
+    }
+}
+
+impl Iterator for Download {
+    type Item = Result<Bytes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.inner.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use http::Method;
+
+    fn client(transport: MemoryTransport) -> Client {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .retry_attempts(0)
+            .build()
+            .unwrap();
+        Client::from_async(crate::Client::with_transport(config, transport).unwrap()).unwrap()
+    }
+
+    fn json_response(body: serde_json::Value) -> Result<http::Response<Bytes>> {
+        Ok(http::Response::builder()
+            .status(200)
+            .header("content-type", "application/json")
+            .body(Bytes::from(body.to_string()))
+            .unwrap())
+    }
+
+    #[test]
+    fn test_resource_calls_and_errors() {
+        let client = client(
+            MemoryTransport::new()
+                .json(Method::GET, "/api/v1/returns", 200, serde_json::json!([]))
+                .json(Method::GET, "/api/v1/shipments/{id}", 404, serde_json::json!({"message": "missing"})),
+        );
+
+        assert!(client.returns().list().unwrap().is_empty());
+
+        let error = client.shipments().get("shp_missing").unwrap_err();
+        assert!(matches!(error, Error::RetryExhausted { ref last_error, .. } if matches!(**last_error, Error::NotFound)));
+    }
+
+    #[test]
+    fn test_pagination_iterator() {
+        let client = client(MemoryTransport::new().route(Method::GET, "/api/v1/items", |request| {
+            match request.url().query() {
+                Some("page=2") => json_response(serde_json::json!({"data": [{"n": 3}]})),
+                _ => json_response(serde_json::json!({
+                    "data": [{"n": 1}, {"n": 2}],
+                    "next_page": "/api/v1/items?page=2",
+                })),
+            }
+        }));
+
+        let items: Vec<serde_json::Value> = client
+            .iter("/api/v1/items")
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(items.iter().map(|item| item["n"].as_u64().unwrap()).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_download_to_writer() {
+        let client = client(MemoryTransport::new().route(Method::GET, "/api/v1/files/{id}", |_| {
+            Ok(http::Response::builder()
+                .status(200)
+                .header("content-length", "11")
+                .body(Bytes::from_static(b"hello world"))
+                .unwrap())
+        }));
+
+        let mut output = Vec::new();
+        let written = client
+            .download_to("/api/v1/files/report", &mut output, |_| {})
+            .unwrap();
+        assert_eq!(written, 11);
+        assert_eq!(output, b"hello world");
+
+        let download = client.download("/api/v1/files/report").unwrap();
+        assert_eq!(download.content_length(), Some(11));
+        assert_eq!(download.bytes().unwrap(), Bytes::from_static(b"hello world"));
+    }
+}
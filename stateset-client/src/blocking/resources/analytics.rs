@@ -0,0 +1,85 @@
+//! Blocking Analytics API
+
+use crate::blocking::{Download, Runtime};
+use crate::resources::analytics as r#async;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::analytics::{
+    AnalyticsQueryRequest, AnalyticsQueryResponse, AnalyticsReport, ChartType,
+    CreateAnalyticsReportRequest, CustomerAnalytics, InventoryAnalytics, ProductAnalytics,
+    RealtimeDashboard, ReportType, SalesAnalytics,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Analytics API client
+    AnalyticsClient => r#async::AnalyticsClient
+}
+
+impl AnalyticsClient {
+    blocking_methods! {
+        /// Create a new analytics report
+        fn create_report(&self, request: CreateAnalyticsReportRequest) -> Result<AnalyticsReport>;
+        /// Get an analytics report by ID
+        fn get_report(&self, id: impl Into<ResourceId>) -> Result<AnalyticsReport>;
+        /// Update an analytics report
+        fn update_report(&self, id: impl Into<ResourceId>, request: CreateAnalyticsReportRequest) -> Result<AnalyticsReport>;
+        /// Delete an analytics report
+        fn delete_report(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Execute an analytics query
+        fn query(&self, request: AnalyticsQueryRequest) -> Result<AnalyticsQueryResponse>;
+        /// Get sales analytics
+        fn sales_analytics(&self, date_range: Option<(String, String)>) -> Result<SalesAnalytics>;
+        /// Get customer analytics
+        fn customer_analytics(&self, date_range: Option<(String, String)>) -> Result<CustomerAnalytics>;
+        /// Get product analytics
+        fn product_analytics(&self, date_range: Option<(String, String)>) -> Result<ProductAnalytics>;
+        /// Get inventory analytics
+        fn inventory_analytics(&self, date_range: Option<(String, String)>) -> Result<InventoryAnalytics>;
+        /// Get real-time dashboard
+        fn realtime_dashboard(&self) -> Result<RealtimeDashboard>;
+        /// Export analytics data
+        fn export(&self, report_id: impl Into<ResourceId>, format: &str) -> Result<Vec<u8>>;
+        /// Get revenue trends
+        fn revenue_trends(&self, period: &str) -> Result<serde_json::Value>;
+        /// Get conversion funnel
+        fn conversion_funnel(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value>;
+        /// Get cohort analysis
+        fn cohort_analysis(&self, cohort_type: &str) -> Result<serde_json::Value>;
+    }
+
+    /// Stream a report export (CSV, XLSX, PDF, ...) without buffering it
+    pub fn export_stream(&self, report_id: impl Into<ResourceId>, format: &str) -> Result<Download> {
+        let download = self.runtime.block_on(self.inner.export_stream(report_id, format))?;
+        Ok(Download::new(download, self.runtime.clone()))
+    }
+
+    /// List analytics reports
+    pub fn list_reports(&self) -> AnalyticsListBuilder {
+        AnalyticsListBuilder {
+            inner: self.inner.list_reports(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing analytics reports
+pub struct AnalyticsListBuilder {
+    inner: r#async::AnalyticsListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl AnalyticsListBuilder {
+    builder_setters! {
+        /// Filter by report type
+        fn report_type(report_type: ReportType);
+        /// Filter by chart type
+        fn chart_type(chart_type: ChartType);
+        /// Filter by realtime flag
+        fn is_realtime(realtime: bool);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<AnalyticsReport>>;
+    }
+}
@@ -0,0 +1,65 @@
+//! Blocking Bill of Materials API
+
+use crate::blocking::Runtime;
+use crate::resources::boms as r#async;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::bom::{
+    Bom, BomCostAnalysis, BomExplosion, BomStatus, BomType, CreateBomRequest, UpdateBomRequest,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Bill of Materials API client
+    BomsClient => r#async::BomsClient
+}
+
+impl BomsClient {
+    blocking_methods! {
+        /// Create a new BOM
+        fn create(&self, request: CreateBomRequest) -> Result<Bom>;
+        /// Get a BOM by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Bom>;
+        /// Update a BOM
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateBomRequest) -> Result<Bom>;
+        /// Delete a BOM
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Approve a BOM
+        fn approve(&self, id: impl Into<ResourceId>) -> Result<Bom>;
+        /// Get BOM cost analysis
+        fn cost_analysis(&self, id: impl Into<ResourceId>) -> Result<BomCostAnalysis>;
+        /// Get BOM explosion (where-used analysis)
+        fn explosion(&self, component_id: impl Into<ResourceId>) -> Result<BomExplosion>;
+        /// Get BOMs by product
+        fn by_product(&self, product_id: impl Into<ResourceId>) -> Result<Vec<Bom>>;
+    }
+
+    /// List BOMs
+    pub fn list(&self) -> BomListBuilder {
+        BomListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing BOMs
+pub struct BomListBuilder {
+    inner: r#async::BomListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl BomListBuilder {
+    builder_setters! {
+        /// Filter by BOM status
+        fn status(status: BomStatus);
+        /// Filter by BOM type
+        fn bom_type(bom_type: BomType);
+        /// Filter by product
+        fn product_id(product_id: impl Into<ResourceId>);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<Bom>>;
+    }
+}
@@ -0,0 +1,83 @@
+//! Blocking Carts API
+
+use crate::blocking::Runtime;
+use crate::resources::carts as r#async;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::cart::{
+    AddCartItemRequest, ApplyCouponRequest, Cart, CartAnalytics, CartStatus, CartType,
+    CreateCartRequest, UpdateCartItemRequest, UpdateCartRequest,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Carts API client
+    CartsClient => r#async::CartsClient
+}
+
+impl CartsClient {
+    blocking_methods! {
+        /// Create a new cart
+        fn create(&self, request: CreateCartRequest) -> Result<Cart>;
+        /// Get a cart by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Cart>;
+        /// Get a cart by token
+        fn get_by_token(&self, token: &str) -> Result<Cart>;
+        /// Update a cart
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateCartRequest) -> Result<Cart>;
+        /// Delete a cart
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Add item to cart
+        fn add_item(&self, cart_id: impl Into<ResourceId>, request: AddCartItemRequest) -> Result<Cart>;
+        /// Update cart item
+        fn update_item(
+            &self,
+            cart_id: impl Into<ResourceId>,
+            item_id: impl Into<ResourceId>,
+            request: UpdateCartItemRequest,
+        ) -> Result<Cart>;
+        /// Remove item from cart
+        fn remove_item(&self, cart_id: impl Into<ResourceId>, item_id: impl Into<ResourceId>) -> Result<Cart>;
+        /// Clear cart
+        fn clear(&self, cart_id: impl Into<ResourceId>) -> Result<Cart>;
+        /// Apply coupon to cart
+        fn apply_coupon(&self, cart_id: impl Into<ResourceId>, request: ApplyCouponRequest) -> Result<Cart>;
+        /// Remove coupon from cart
+        fn remove_coupon(&self, cart_id: impl Into<ResourceId>, coupon_code: &str) -> Result<Cart>;
+        /// Convert cart to order
+        fn convert_to_order(&self, cart_id: impl Into<ResourceId>) -> Result<serde_json::Value>;
+        /// Get cart analytics
+        fn analytics(&self, date_range: Option<(String, String)>) -> Result<CartAnalytics>;
+        /// Get abandoned carts
+        fn abandoned(&self) -> Result<Vec<Cart>>;
+    }
+
+    /// List carts
+    pub fn list(&self) -> CartListBuilder {
+        CartListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing carts
+pub struct CartListBuilder {
+    inner: r#async::CartListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl CartListBuilder {
+    builder_setters! {
+        /// Filter by cart status
+        fn status(status: CartStatus);
+        /// Filter by cart type
+        fn cart_type(cart_type: CartType);
+        /// Filter by customer
+        fn customer_id(customer_id: impl Into<ResourceId>);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<Cart>>;
+    }
+}
@@ -0,0 +1,82 @@
+//! Blocking Checkouts API
+
+use crate::blocking::Runtime;
+use crate::resources::checkouts as r#async;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::checkout::{
+    ApplyCheckoutCouponRequest, ApplyGiftCardRequest, Checkout, CheckoutAbandonmentAnalysis,
+    CheckoutCompletionResult, CheckoutStatus, CheckoutStep, CompleteCheckoutRequest,
+    CreateCheckoutRequest, UpdateCheckoutRequest,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Checkouts API client
+    CheckoutsClient => r#async::CheckoutsClient
+}
+
+impl CheckoutsClient {
+    blocking_methods! {
+        /// Create a new checkout
+        fn create(&self, request: CreateCheckoutRequest) -> Result<Checkout>;
+        /// Get a checkout by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Checkout>;
+        /// Get a checkout by token
+        fn get_by_token(&self, token: &str) -> Result<Checkout>;
+        /// Update a checkout
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateCheckoutRequest) -> Result<Checkout>;
+        /// Complete a checkout
+        fn complete(&self, id: impl Into<ResourceId>, request: CompleteCheckoutRequest) -> Result<CheckoutCompletionResult>;
+        /// Complete a checkout with a caller-supplied idempotency key
+        fn complete_with_idempotency_key(
+            &self,
+            id: impl Into<ResourceId>,
+            request: CompleteCheckoutRequest,
+            key: impl AsRef<str>,
+        ) -> Result<CheckoutCompletionResult>;
+        /// Cancel a checkout
+        fn cancel(&self, id: impl Into<ResourceId>) -> Result<Checkout>;
+        /// Apply coupon to checkout
+        fn apply_coupon(&self, id: impl Into<ResourceId>, request: ApplyCheckoutCouponRequest) -> Result<Checkout>;
+        /// Apply gift card to checkout
+        fn apply_gift_card(&self, id: impl Into<ResourceId>, request: ApplyGiftCardRequest) -> Result<Checkout>;
+        /// Get shipping rates for checkout
+        fn get_shipping_rates(&self, id: impl Into<ResourceId>) -> Result<serde_json::Value>;
+        /// Update checkout step
+        fn update_step(&self, id: impl Into<ResourceId>, step: CheckoutStep) -> Result<Checkout>;
+        /// Get checkout abandonment analysis
+        fn abandonment_analysis(&self, date_range: Option<(String, String)>) -> Result<CheckoutAbandonmentAnalysis>;
+        /// Get abandoned checkouts
+        fn abandoned(&self) -> Result<Vec<Checkout>>;
+    }
+
+    /// List checkouts
+    pub fn list(&self) -> CheckoutListBuilder {
+        CheckoutListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing checkouts
+pub struct CheckoutListBuilder {
+    inner: r#async::CheckoutListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl CheckoutListBuilder {
+    builder_setters! {
+        /// Filter by checkout status
+        fn status(status: CheckoutStatus);
+        /// Filter by current step
+        fn current_step(step: CheckoutStep);
+        /// Filter by customer
+        fn customer_id(customer_id: impl Into<ResourceId>);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<Checkout>>;
+    }
+}
@@ -0,0 +1,79 @@
+//! Blocking Inventory API
+
+use crate::blocking::Runtime;
+use crate::resources::inventory as r#async;
+use stateset_core::{Result, types::ResourceId};
+use stateset_models::inventory::{
+    CreateReservationRequest, InventoryLevel, InventoryReservation, InventoryUpdate,
+    ReservationStrategy,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+blocking_resource! {
+    /// Blocking Inventory API client
+    InventoryClient => r#async::InventoryClient
+}
+
+impl InventoryClient {
+    blocking_methods! {
+        /// Get inventory levels for a specific item
+        fn get_levels(&self, item_id: impl Into<ResourceId>) -> Result<Vec<InventoryLevel>>;
+        /// Get inventory level at a specific location
+        fn get_level(&self, item_id: impl Into<ResourceId>, location_id: impl Into<ResourceId>) -> Result<InventoryLevel>;
+        /// Update inventory levels (adjustments)
+        fn adjust(&self, updates: Vec<InventoryUpdate>) -> Result<Vec<InventoryLevel>>;
+        /// Update inventory in batch
+        fn update_batch(&self, updates: Vec<InventoryUpdate>) -> Result<Vec<InventoryLevel>>;
+        /// Create an inventory reservation
+        fn create_reservation(&self, request: CreateReservationRequest) -> Result<InventoryReservation>;
+        /// Create an inventory reservation with a caller-supplied idempotency key
+        fn create_reservation_with_idempotency_key(
+            &self,
+            request: CreateReservationRequest,
+            key: impl AsRef<str>,
+        ) -> Result<InventoryReservation>;
+        /// Get a reservation by ID
+        fn get_reservation(&self, id: impl Into<ResourceId>) -> Result<InventoryReservation>;
+        /// Cancel a reservation
+        fn cancel_reservation(&self, id: impl Into<ResourceId>) -> Result<InventoryReservation>;
+    }
+
+    /// Start building a reservation
+    pub fn reserve(&self) -> InventoryReservationBuilder {
+        InventoryReservationBuilder {
+            inner: self.inner.reserve(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for inventory reservations
+pub struct InventoryReservationBuilder {
+    inner: r#async::InventoryReservationBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl InventoryReservationBuilder {
+    builder_setters! {
+        /// Set the warehouse
+        fn warehouse(warehouse_id: impl Into<String>);
+        /// Set the reference (e.g., order ID)
+        fn reference(id: impl Into<ResourceId>, ref_type: stateset_core::types::ReferenceType);
+        /// Add an item to reserve
+        fn item(item_id: impl Into<ResourceId>, quantity: u32);
+        /// Add items from an order
+        fn items(items: &[stateset_models::order::OrderItem]);
+        /// Set the reservation strategy
+        fn strategy(strategy: ReservationStrategy);
+        /// Set the reservation duration
+        fn duration(duration: Duration);
+        /// Set the priority
+        fn priority(priority: u8);
+    }
+
+    consuming_methods! {
+        /// Execute the reservation
+        fn execute(self) -> Result<InventoryReservation>;
+    }
+}
@@ -0,0 +1,13 @@
+//! Blocking counterparts of the resource clients
+
+pub mod analytics;
+pub mod boms;
+pub mod carts;
+pub mod checkouts;
+pub mod inventory;
+pub mod orders;
+pub mod products;
+pub mod returns;
+pub mod shipments;
+pub mod warranties;
+pub mod work_orders;
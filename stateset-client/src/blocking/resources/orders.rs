@@ -0,0 +1,143 @@
+//! Blocking Orders API
+
+use crate::blocking::{Iter, Runtime};
+use crate::request::SortOrder;
+use crate::resources::orders as r#async;
+use stateset_core::{ListResponse, Result, types::{ResourceId, Timestamp}};
+use stateset_models::order::{CreateOrderRequest, Order, OrderStatus, UpdateOrderRequest};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Orders API client
+    OrdersClient => r#async::OrdersClient
+}
+
+impl OrdersClient {
+    blocking_methods! {
+        /// Create a new order
+        fn create(&self, request: CreateOrderRequest) -> Result<Order>;
+        /// Create a new order with a caller-supplied idempotency key
+        fn create_with_idempotency_key(&self, request: CreateOrderRequest, key: impl AsRef<str>) -> Result<Order>;
+        /// Get an order by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Order>;
+        /// Update an order
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateOrderRequest) -> Result<Order>;
+        /// Delete an order
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Cancel an order
+        fn cancel(&self, id: impl Into<ResourceId>) -> Result<Order>;
+        /// Fulfill an order
+        fn fulfill(&self, id: impl Into<ResourceId>) -> Result<Order>;
+        /// Refund an order
+        fn refund(&self, id: impl Into<ResourceId>, amount: Option<f64>) -> Result<Order>;
+        /// Create multiple orders in batch
+        fn create_batch(&self, orders: Vec<CreateOrderRequest>) -> Result<Vec<Order>>;
+        /// Get order analytics
+        fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value>;
+    }
+
+    /// List orders with a builder pattern
+    pub fn list(&self) -> OrderListBuilder {
+        OrderListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// Start building an update request
+    pub fn update_builder(&self, id: impl Into<ResourceId>) -> OrderUpdateBuilder {
+        OrderUpdateBuilder {
+            inner: self.inner.update_builder(id),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing orders
+#[derive(Clone)]
+pub struct OrderListBuilder {
+    inner: r#async::OrderListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl OrderListBuilder {
+    builder_setters! {
+        /// Filter by order status
+        fn status(status: OrderStatus);
+        /// Filter by customer ID
+        fn customer(customer_id: impl Into<ResourceId>);
+        /// Filter by date range
+        fn date_range(start: Timestamp, end: Timestamp);
+        /// Filter by minimum total amount (in cents)
+        fn min_total(amount: i64);
+        /// Filter by maximum total amount (in cents)
+        fn max_total(amount: i64);
+        /// Set the page limit
+        fn limit(limit: u32);
+        /// Set the page number
+        fn page(page: u32);
+        /// Set the cursor for cursor-based pagination
+        fn cursor(cursor: String);
+        /// Sort by creation date
+        fn sort_by_created_at(order: SortOrder);
+        /// Sort by total amount
+        fn sort_by_total(order: SortOrder);
+        /// Sort by updated date
+        fn sort_by_updated_at(order: SortOrder);
+    }
+
+    blocking_methods! {
+        /// Execute the request and return a single page
+        fn execute(&self) -> Result<ListResponse<Order>>;
+        /// Count total results without fetching all data
+        fn count(&self) -> Result<u64>;
+    }
+
+    consuming_methods! {
+        /// Collect all results into a vector (use with caution for large datasets)
+        fn collect_all(self) -> Result<Vec<Order>>;
+    }
+
+    /// Iterate over all pages of results, fetching them as needed
+    pub fn iter(self) -> Iter<Order> {
+        Iter::new(self.inner.stream(), self.runtime)
+    }
+}
+
+impl IntoIterator for OrderListBuilder {
+    type Item = Result<Order>;
+    type IntoIter = Iter<Order>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Blocking builder for updating orders
+pub struct OrderUpdateBuilder {
+    inner: r#async::OrderUpdateBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl OrderUpdateBuilder {
+    builder_setters! {
+        /// Update the order status
+        fn status(status: OrderStatus);
+        /// Update the tracking number
+        fn tracking_number(tracking: impl Into<String>);
+        /// Update notes
+        fn notes(notes: impl Into<String>);
+        /// Update shipping address
+        fn shipping_address(address: stateset_core::types::Address);
+    }
+
+    /// Validate the update request
+    pub fn validate(&self) -> Result<()> {
+        self.inner.validate()
+    }
+
+    consuming_methods! {
+        /// Execute the update with validation
+        fn execute(self) -> Result<Order>;
+    }
+}
@@ -0,0 +1,77 @@
+//! Blocking Products API
+
+use crate::blocking::Runtime;
+use crate::resources::products as r#async;
+use crate::upload::UploadFile;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::product::{
+    CreateProductRequest, Product, ProductDocument, ProductImage, ProductStatus, ProductType,
+    UpdateProductRequest,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Products API client
+    ProductsClient => r#async::ProductsClient
+}
+
+impl ProductsClient {
+    blocking_methods! {
+        /// Create a new product
+        fn create(&self, request: CreateProductRequest) -> Result<Product>;
+        /// Get a product by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Product>;
+        /// Get a product by SKU
+        fn get_by_sku(&self, sku: &str) -> Result<Product>;
+        /// Update a product
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateProductRequest) -> Result<Product>;
+        /// Delete a product
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Duplicate a product
+        fn duplicate(&self, id: impl Into<ResourceId>) -> Result<Product>;
+        /// Upload a product image
+        fn upload_image(&self, id: impl Into<ResourceId>, file: UploadFile, alt_text: Option<&str>) -> Result<ProductImage>;
+        /// Upload a product document (manual, spec sheet, certificate, ...)
+        fn upload_document(&self, id: impl Into<ResourceId>, file: UploadFile, title: Option<&str>) -> Result<ProductDocument>;
+        /// Search products
+        fn search(&self, query: &str) -> Result<Vec<Product>>;
+        /// Get products by category
+        fn by_category(&self, category_id: impl Into<ResourceId>) -> Result<Vec<Product>>;
+        /// Get products by brand
+        fn by_brand(&self, brand_id: impl Into<ResourceId>) -> Result<Vec<Product>>;
+    }
+
+    /// List products
+    pub fn list(&self) -> ProductListBuilder {
+        ProductListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing products
+pub struct ProductListBuilder {
+    inner: r#async::ProductListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl ProductListBuilder {
+    builder_setters! {
+        /// Filter by product status
+        fn status(status: ProductStatus);
+        /// Filter by product type
+        fn product_type(product_type: ProductType);
+        /// Filter by category
+        fn category_id(category_id: impl Into<ResourceId>);
+        /// Filter by brand
+        fn brand_id(brand_id: impl Into<ResourceId>);
+        /// Filter by featured status
+        fn featured(featured: bool);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<Product>>;
+    }
+}
@@ -0,0 +1,35 @@
+//! Blocking Returns API
+
+use crate::resources::returns as r#async;
+use stateset_core::{Result, types::ResourceId};
+use stateset_models::returns::{CreateReturnRequest, Return, UpdateReturnRequest};
+
+blocking_resource! {
+    /// Blocking Returns API client
+    ReturnsClient => r#async::ReturnsClient
+}
+
+impl ReturnsClient {
+    blocking_methods! {
+        /// Create a new return
+        fn create(&self, request: CreateReturnRequest) -> Result<Return>;
+        /// Get a return by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Return>;
+        /// Update a return
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateReturnRequest) -> Result<Return>;
+        /// Approve a return
+        fn approve(&self, id: impl Into<ResourceId>) -> Result<Return>;
+        /// Reject a return
+        fn reject(&self, id: impl Into<ResourceId>, reason: &str) -> Result<Return>;
+        /// Mark a return as received
+        fn receive(&self, id: impl Into<ResourceId>) -> Result<Return>;
+        /// Process a return (issue refund/exchange)
+        fn process(&self, id: impl Into<ResourceId>) -> Result<Return>;
+        /// List returns
+        fn list(&self) -> Result<Vec<Return>>;
+        /// List returns for a specific order
+        fn list_by_order(&self, order_id: impl Into<ResourceId>) -> Result<Vec<Return>>;
+        /// List returns for a specific customer
+        fn list_by_customer(&self, customer_id: impl Into<ResourceId>) -> Result<Vec<Return>>;
+    }
+}
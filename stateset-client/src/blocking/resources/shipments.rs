@@ -0,0 +1,46 @@
+//! Blocking Shipments API
+
+use crate::blocking::Download;
+use crate::resources::shipments::{self as r#async, RateRequest, ShippingLabel, ShippingRate, TrackingEvent};
+use stateset_core::{Result, types::ResourceId};
+use stateset_models::shipment::{CreateShipmentRequest, Shipment, UpdateShipmentRequest};
+
+blocking_resource! {
+    /// Blocking Shipments API client
+    ShipmentsClient => r#async::ShipmentsClient
+}
+
+impl ShipmentsClient {
+    blocking_methods! {
+        /// Create a new shipment
+        fn create(&self, request: CreateShipmentRequest) -> Result<Shipment>;
+        /// Get a shipment by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Shipment>;
+        /// Get a shipment by tracking number
+        fn get_by_tracking(&self, tracking_number: &str) -> Result<Shipment>;
+        /// Update a shipment
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateShipmentRequest) -> Result<Shipment>;
+        /// Cancel a shipment
+        fn cancel(&self, id: impl Into<ResourceId>) -> Result<Shipment>;
+        /// Mark a shipment as shipped
+        fn ship(&self, id: impl Into<ResourceId>) -> Result<Shipment>;
+        /// Mark a shipment as delivered
+        fn deliver(&self, id: impl Into<ResourceId>) -> Result<Shipment>;
+        /// Get tracking events for a shipment
+        fn get_tracking_events(&self, id: impl Into<ResourceId>) -> Result<Vec<TrackingEvent>>;
+        /// List shipments
+        fn list(&self) -> Result<Vec<Shipment>>;
+        /// List shipments for a specific order
+        fn list_by_order(&self, order_id: impl Into<ResourceId>) -> Result<Vec<Shipment>>;
+        /// Create shipping labels
+        fn create_label(&self, id: impl Into<ResourceId>) -> Result<ShippingLabel>;
+        /// Get shipping rates
+        fn get_rates(&self, request: RateRequest) -> Result<Vec<ShippingRate>>;
+    }
+
+    /// Download the label document (PDF, ZPL, PNG, ...) of a shipment
+    pub fn download_label(&self, id: impl Into<ResourceId>, format: &str) -> Result<Download> {
+        let download = self.runtime.block_on(self.inner.download_label(id, format))?;
+        Ok(Download::new(download, self.runtime.clone()))
+    }
+}
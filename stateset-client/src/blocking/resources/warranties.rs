@@ -0,0 +1,116 @@
+//! Blocking Warranties API
+
+use crate::blocking::Runtime;
+use crate::resources::warranties as r#async;
+use crate::upload::UploadFile;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::warranty::{
+    CreateWarrantyClaimRequest, CreateWarrantyRequest, UpdateWarrantyClaimRequest,
+    UpdateWarrantyRequest, Warranty, WarrantyClaim, WarrantyClaimAttachment, WarrantyStatus,
+    WarrantyType,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Warranties API client
+    WarrantiesClient => r#async::WarrantiesClient
+}
+
+impl WarrantiesClient {
+    blocking_methods! {
+        /// Create a new warranty
+        fn create(&self, request: CreateWarrantyRequest) -> Result<Warranty>;
+        /// Get a warranty by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<Warranty>;
+        /// Update a warranty
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateWarrantyRequest) -> Result<Warranty>;
+        /// Delete a warranty
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Activate a warranty
+        fn activate(&self, id: impl Into<ResourceId>) -> Result<Warranty>;
+        /// Void a warranty
+        fn void(&self, id: impl Into<ResourceId>, reason: Option<String>) -> Result<Warranty>;
+        /// Transfer a warranty to another customer
+        fn transfer(&self, id: impl Into<ResourceId>, new_customer_id: impl Into<ResourceId>) -> Result<Warranty>;
+        /// Create a warranty claim
+        fn create_claim(&self, request: CreateWarrantyClaimRequest) -> Result<WarrantyClaim>;
+        /// Get a warranty claim by ID
+        fn get_claim(&self, id: impl Into<ResourceId>) -> Result<WarrantyClaim>;
+        /// Update a warranty claim
+        fn update_claim(&self, id: impl Into<ResourceId>, request: UpdateWarrantyClaimRequest) -> Result<WarrantyClaim>;
+        /// Approve a warranty claim
+        fn approve_claim(&self, id: impl Into<ResourceId>, approved_amount: Option<serde_json::Value>) -> Result<WarrantyClaim>;
+        /// Deny a warranty claim
+        fn deny_claim(&self, id: impl Into<ResourceId>, reason: String) -> Result<WarrantyClaim>;
+        /// Upload an attachment (photo, receipt, ...) to a warranty claim
+        fn upload_claim_attachment(&self, claim_id: impl Into<ResourceId>, file: UploadFile) -> Result<WarrantyClaimAttachment>;
+        /// Get warranties by customer
+        fn by_customer(&self, customer_id: impl Into<ResourceId>) -> Result<Vec<Warranty>>;
+        /// Get warranties by product
+        fn by_product(&self, product_id: impl Into<ResourceId>) -> Result<Vec<Warranty>>;
+        /// Get warranty analytics
+        fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value>;
+    }
+
+    /// List warranties with a builder pattern
+    pub fn list(&self) -> WarrantyListBuilder {
+        WarrantyListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+
+    /// List warranty claims with a builder pattern
+    pub fn list_claims(&self) -> WarrantyClaimListBuilder {
+        WarrantyClaimListBuilder {
+            inner: self.inner.list_claims(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing warranties
+pub struct WarrantyListBuilder {
+    inner: r#async::WarrantyListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl WarrantyListBuilder {
+    builder_setters! {
+        /// Filter by warranty status
+        fn status(status: WarrantyStatus);
+        /// Filter by warranty type
+        fn warranty_type(warranty_type: WarrantyType);
+        /// Filter by customer
+        fn customer_id(customer_id: impl Into<ResourceId>);
+        /// Filter by product
+        fn product_id(product_id: impl Into<ResourceId>);
+        /// Set the page limit
+        fn limit(limit: u32);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<Warranty>>;
+    }
+}
+
+/// Blocking builder for listing warranty claims
+pub struct WarrantyClaimListBuilder {
+    inner: r#async::WarrantyClaimListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl WarrantyClaimListBuilder {
+    builder_setters! {
+        /// Filter by warranty ID
+        fn warranty_id(warranty_id: impl Into<ResourceId>);
+        /// Set the page limit
+        fn limit(limit: u32);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a single page
+        fn execute(self) -> Result<ListResponse<WarrantyClaim>>;
+    }
+}
@@ -0,0 +1,116 @@
+//! Blocking Work Orders API
+
+use crate::blocking::{Iter, Runtime};
+use crate::request::SortOrder;
+use crate::resources::work_orders as r#async;
+use crate::upload::UploadFile;
+use stateset_core::{ListResponse, Result, types::ResourceId};
+use stateset_models::work_order::{
+    CreateWorkOrderRequest, UpdateWorkOrderRequest, WorkOrder, WorkOrderAttachment,
+    WorkOrderPriority, WorkOrderStatus, WorkOrderType,
+};
+use std::sync::Arc;
+
+blocking_resource! {
+    /// Blocking Work Orders API client
+    WorkOrdersClient => r#async::WorkOrdersClient
+}
+
+impl WorkOrdersClient {
+    blocking_methods! {
+        /// Create a new work order
+        fn create(&self, request: CreateWorkOrderRequest) -> Result<WorkOrder>;
+        /// Get a work order by ID
+        fn get(&self, id: impl Into<ResourceId>) -> Result<WorkOrder>;
+        /// Update a work order
+        fn update(&self, id: impl Into<ResourceId>, request: UpdateWorkOrderRequest) -> Result<WorkOrder>;
+        /// Delete a work order
+        fn delete(&self, id: impl Into<ResourceId>) -> Result<()>;
+        /// Start a work order
+        fn start(&self, id: impl Into<ResourceId>) -> Result<WorkOrder>;
+        /// Complete a work order
+        fn complete(&self, id: impl Into<ResourceId>, completion_notes: Option<String>) -> Result<WorkOrder>;
+        /// Cancel a work order
+        fn cancel(&self, id: impl Into<ResourceId>) -> Result<WorkOrder>;
+        /// Put a work order on hold
+        fn hold(&self, id: impl Into<ResourceId>, reason: Option<String>) -> Result<WorkOrder>;
+        /// Resume a work order from hold
+        fn resume(&self, id: impl Into<ResourceId>) -> Result<WorkOrder>;
+        /// Assign a work order to a user
+        fn assign(&self, id: impl Into<ResourceId>, user_id: impl Into<ResourceId>) -> Result<WorkOrder>;
+        /// Upload an attachment (manual, photo, sign-off sheet, ...) to a work order
+        fn upload_attachment(&self, id: impl Into<ResourceId>, file: UploadFile) -> Result<WorkOrderAttachment>;
+        /// Create multiple work orders in batch
+        fn create_batch(&self, work_orders: Vec<CreateWorkOrderRequest>) -> Result<Vec<WorkOrder>>;
+        /// Get work order analytics
+        fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value>;
+        /// Get work orders by asset
+        fn by_asset(&self, asset_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>>;
+        /// Get work orders by customer
+        fn by_customer(&self, customer_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>>;
+        /// Get work orders assigned to a user
+        fn by_assignee(&self, user_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>>;
+    }
+
+    /// List work orders with a builder pattern
+    pub fn list(&self) -> WorkOrderListBuilder {
+        WorkOrderListBuilder {
+            inner: self.inner.list(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking builder for listing work orders
+pub struct WorkOrderListBuilder {
+    inner: r#async::WorkOrderListBuilder,
+    runtime: Arc<Runtime>,
+}
+
+impl WorkOrderListBuilder {
+    builder_setters! {
+        /// Filter by work order status
+        fn status(status: WorkOrderStatus);
+        /// Filter by work order priority
+        fn priority(priority: WorkOrderPriority);
+        /// Filter by work order type
+        fn work_order_type(work_order_type: WorkOrderType);
+        /// Filter by assigned user
+        fn assigned_to(user_id: impl Into<ResourceId>);
+        /// Filter by customer
+        fn customer_id(customer_id: impl Into<ResourceId>);
+        /// Filter by asset
+        fn asset_id(asset_id: impl Into<ResourceId>);
+        /// Filter by creation date range
+        fn created_between(start: impl Into<String>, end: impl Into<String>);
+        /// Filter by scheduled date range
+        fn scheduled_between(start: impl Into<String>, end: impl Into<String>);
+        /// Set the number of items per page
+        fn limit(limit: u32);
+        /// Set the page offset
+        fn offset(offset: u32);
+        /// Set the sort field and order
+        fn sort_by(field: &str, order: SortOrder);
+    }
+
+    consuming_methods! {
+        /// Execute the query and return a paginated response
+        fn execute(self) -> Result<ListResponse<WorkOrder>>;
+        /// Execute the query and return all results as a vector
+        fn all(self) -> Result<Vec<WorkOrder>>;
+    }
+
+    /// Iterate over all work orders, fetching pages as needed
+    pub fn iter(self) -> Iter<WorkOrder> {
+        Iter::new(self.inner.stream(), self.runtime)
+    }
+}
+
+impl IntoIterator for WorkOrderListBuilder {
+    type Item = Result<WorkOrder>;
+    type IntoIter = Iter<WorkOrder>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,765 @@
+//! HTTP response cache with conditional revalidation
+//!
+//! With a [`ResponseCache`] attached through
+//! [`Client::with_cache`](crate::Client::with_cache), successful `GET`
+//! responses are stored under their URL and the caller's credentials. While
+//! an entry is fresh according to `Cache-Control: max-age` it is served
+//! without a request; once stale it is revalidated with `If-None-Match` /
+//! `If-Modified-Since`, and a `304 Not Modified` is answered from the cache.
+//! With an API version header configured, its value is part of the key too.
+//!
+//! Responses marked `no-store`, and responses with neither a lifetime nor a
+//! validator, are never stored. A successful write (`POST`, `PUT`, `PATCH`,
+//! `DELETE`) to a URL evicts the cached `GET`s of that URL and of its parent
+//! paths up to the resource collection, whatever their query string, so
+//! `POST /api/v1/orders/{id}/cancel` also evicts `GET /api/v1/orders/{id}`
+//! and every cached page of `GET /api/v1/orders?...`.
+
+use crate::response::HttpResponse;
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stateset_core::{Error, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Headers that describe the stored body and are not replaced by a 304
+const ENTITY_HEADERS: &[&str] = &["content-length", "content-encoding", "content-type"];
+
+/// A stored response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// Response headers as name/value pairs
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    #[serde(skip)]
+    pub body: Bytes,
+    /// When the response was received or last revalidated, in seconds since
+    /// the Unix epoch
+    pub stored_at: u64,
+}
+
+impl CachedResponse {
+    fn from_response(response: &HttpResponse) -> Self {
+        Self {
+            headers: header_pairs(response.headers()),
+            body: response.body().clone(),
+            stored_at: unix_now(),
+        }
+    }
+
+    /// Get a header value
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Entity tag to revalidate with
+    pub fn etag(&self) -> Option<&str> {
+        self.header("etag")
+    }
+
+    /// Modification date to revalidate with
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header("last-modified")
+    }
+
+    /// Whether the entry may be served without asking the server
+    pub fn is_fresh(&self) -> bool {
+        let directives = CacheControl::parse(self.header("cache-control"));
+        let age = Duration::from_secs(unix_now().saturating_sub(self.stored_at));
+        !directives.no_cache && directives.max_age.is_some_and(|max_age| age < max_age)
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers
+    }
+
+    /// Refresh the entry with the headers of a `304 Not Modified`
+    fn revalidate(&mut self, not_modified: &HeaderMap) {
+        let mut headers = self.headers();
+        for name in not_modified.keys() {
+            if ENTITY_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            headers.remove(name);
+            for value in not_modified.get_all(name) {
+                headers.append(name.clone(), value.clone());
+            }
+        }
+        self.headers = header_pairs(&headers);
+        self.stored_at = unix_now();
+    }
+}
+
+/// Storage backend for cached responses
+///
+/// Backends are called synchronously while a request is in flight, so they
+/// should be quick; keys are opaque strings.
+pub trait CacheStore: Send + Sync {
+    /// Look up an entry
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Store or replace an entry
+    fn put(&self, key: &str, response: CachedResponse);
+
+    /// Remove an entry
+    fn remove(&self, key: &str);
+
+    /// Remove every entry whose key `matches`
+    fn remove_matching(&self, matches: &dyn Fn(&str) -> bool);
+}
+
+/// In-memory store evicting the least recently used entry when full
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    entries: Mutex<MemoryEntries>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryEntries {
+    clock: u64,
+    map: HashMap<String, (CachedResponse, u64)>,
+}
+
+impl MemoryCache {
+    /// Keep up to `capacity` responses
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(MemoryEntries::default()),
+        }
+    }
+
+    /// Number of stored responses
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryEntries> {
+        match self.entries.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.lock();
+        entries.clock += 1;
+        let clock = entries.clock;
+        entries.map.get_mut(key).map(|(response, used)| {
+            *used = clock;
+            response.clone()
+        })
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        let mut entries = self.lock();
+        entries.clock += 1;
+        let clock = entries.clock;
+
+        if !entries.map.contains_key(key) && entries.map.len() >= self.capacity {
+            let oldest = entries
+                .map
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.map.remove(&oldest);
+            }
+        }
+        entries.map.insert(key.to_string(), (response, clock));
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().map.remove(key);
+    }
+
+    fn remove_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        self.lock().map.retain(|key, _| !matches(key));
+    }
+}
+
+/// On-disk store keeping one file per response in a directory
+///
+/// Each file holds a JSON header line followed by the raw body. Entries
+/// survive restarts and can be shared by processes using the same directory.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    #[serde(flatten)]
+    response: CachedResponse,
+}
+
+impl DiskCache {
+    /// Store responses in `dir`, creating it if needed
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            Error::config_with_hint(
+                format!("Cannot create cache directory {}: {}", dir.display(), e),
+                "Choose a writable directory for the response cache",
+            )
+        })?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(hex_digest(key))
+    }
+
+    fn read(&self, key: &str) -> std::io::Result<Option<CachedResponse>> {
+        let data = match std::fs::read(self.path(key)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let split = data
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "missing header line"))?;
+        let entry: DiskEntry = serde_json::from_slice(&data[..split])?;
+
+        // Guard against digest collisions
+        if entry.key != key {
+            return Ok(None);
+        }
+        let mut response = entry.response;
+        response.body = Bytes::copy_from_slice(&data[split + 1..]);
+        Ok(Some(response))
+    }
+
+    /// Key stored in the header line of an entry file
+    fn key_of(path: &std::path::Path) -> std::io::Result<String> {
+        #[derive(Deserialize)]
+        struct Header {
+            key: String,
+        }
+
+        let mut line = Vec::new();
+        std::io::BufReader::new(std::fs::File::open(path)?).read_until(b'\n', &mut line)?;
+        Ok(serde_json::from_slice::<Header>(&line)?.key)
+    }
+
+    fn write(&self, key: &str, response: CachedResponse) -> std::io::Result<()> {
+        let body = response.body.clone();
+        let entry = DiskEntry {
+            key: key.to_string(),
+            response,
+        };
+        let mut data = serde_json::to_vec(&entry)?;
+        data.push(b'\n');
+        data.extend_from_slice(&body);
+
+        // Write to a temporary file first so readers never see a partial entry
+        let path = self.path(key);
+        let temp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::write(&temp, data)?;
+        std::fs::rename(&temp, &path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.read(key)
+            .unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable cache entry in {}: {}", self.dir.display(), e);
+                None
+            })
+    }
+
+    fn put(&self, key: &str, response: CachedResponse) {
+        if let Err(e) = self.write(key, response) {
+            log::warn!("Failed to write cache entry to {}: {}", self.dir.display(), e);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+
+    fn remove_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to list cache entries in {}: {}", self.dir.display(), e);
+                return;
+            }
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            // Temporary files have an extension; entries are bare digests
+            if path.extension().is_none() && Self::key_of(&path).is_ok_and(|key| matches(&key)) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Cache hit and miss counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Fresh entries served without a request
+    pub hits: u64,
+    /// Stale entries confirmed by a `304 Not Modified`
+    pub revalidated: u64,
+    /// Requests that fetched a full body
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    revalidated: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Response cache shared by clients
+///
+/// Clones share the store and statistics.
+#[derive(Clone)]
+pub struct ResponseCache {
+    store: Arc<dyn CacheStore>,
+    counters: Arc<CacheCounters>,
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseCache {
+    /// Cache responses in a custom store
+    pub fn new(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Cache up to `capacity` responses in memory
+    pub fn memory(capacity: usize) -> Self {
+        Self::new(MemoryCache::new(capacity))
+    }
+
+    /// Cache responses on disk in `dir`
+    pub fn disk(dir: impl Into<PathBuf>) -> Result<Self> {
+        DiskCache::new(dir).map(Self::new)
+    }
+
+    /// Hits, revalidations and misses so far
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            revalidated: self.counters.revalidated.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Decide what the cache does for a request about to be sent
+    ///
+    /// `GET`s are looked up, with validators attached if the entry is stale;
+    /// writes evict the `GET`s of the same URL and its parents, with any
+    /// query string, once they succeed. `version_header` names the API version header, if any.
+    pub(crate) fn prepare(
+        &self,
+        request: &mut reqwest::Request,
+        version_header: Option<&str>,
+    ) -> Option<CacheAction> {
+        if !request.method().is_safe() {
+            let mut url = request.url().clone();
+            url.set_query(None);
+            url.set_fragment(None);
+            let keys = std::iter::once(url)
+                .chain(parent_urls(request.url()))
+                .map(|url| key_for(request, version_header, &url))
+                .collect();
+            return Some(CacheAction::Invalidate { keys });
+        }
+        if request.method() != Method::GET {
+            return None;
+        }
+
+        let directives = CacheControl::parse(
+            request
+                .headers()
+                .get(reqwest::header::CACHE_CONTROL)
+                .and_then(|v| v.to_str().ok()),
+        );
+        if directives.no_store {
+            return None;
+        }
+
+        let key = request_key(request, version_header);
+        let entry = self.store.get(&key).filter(|_| !directives.no_cache);
+        if let Some(entry) = entry.as_ref().filter(|entry| !entry.is_fresh()) {
+            let headers = request.headers_mut();
+            if let Some(etag) = entry.etag().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(reqwest::header::IF_NONE_MATCH, etag);
+            } else if let Some(date) = entry.last_modified().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(reqwest::header::IF_MODIFIED_SINCE, date);
+            }
+        }
+        Some(CacheAction::Lookup { key, entry })
+    }
+
+    /// Serve a fresh entry without a request
+    pub(crate) fn hit(&self, action: &CacheAction, url: &url::Url) -> Option<HttpResponse> {
+        match action {
+            CacheAction::Lookup { entry: Some(entry), .. } if entry.is_fresh() => {
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(HttpResponse::new(
+                    Method::GET,
+                    url.clone(),
+                    StatusCode::OK,
+                    entry.headers(),
+                    entry.body.clone(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Update the cache with a successful response
+    ///
+    /// Returns the response to hand to the caller and whether it was served
+    /// from the cache, which is the case for a `304 Not Modified`.
+    pub(crate) fn complete(&self, action: CacheAction, response: HttpResponse) -> (HttpResponse, bool) {
+        let (key, entry) = match action {
+            CacheAction::Invalidate { keys } => {
+                self.store.remove_matching(&|stored| {
+                    keys.iter().any(|key| {
+                        stored
+                            .strip_prefix(key.as_str())
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('?'))
+                    })
+                });
+                return (response, false);
+            }
+            CacheAction::Lookup { key, entry } => (key, entry),
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            let Some(mut entry) = entry else {
+                return (response, false);
+            };
+            self.counters.revalidated.fetch_add(1, Ordering::Relaxed);
+            entry.revalidate(response.headers());
+            self.store.put(&key, entry.clone());
+
+            let mut headers = entry.headers();
+            match response.request_id().and_then(|id| HeaderValue::from_str(id).ok()) {
+                Some(id) => headers.insert("x-request-id", id),
+                None => headers.remove("x-request-id"),
+            };
+            let cached = HttpResponse::new(
+                Method::GET,
+                response.url().clone(),
+                StatusCode::OK,
+                headers,
+                entry.body,
+            );
+            return (cached, true);
+        }
+
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        if response.status() == StatusCode::OK && is_storable(response.headers()) {
+            self.store.put(&key, CachedResponse::from_response(&response));
+        } else {
+            self.store.remove(&key);
+        }
+        (response, false)
+    }
+}
+
+/// What the cache does for one request
+#[derive(Debug)]
+pub(crate) enum CacheAction {
+    /// Serve or revalidate a stored `GET`
+    Lookup {
+        key: String,
+        entry: Option<CachedResponse>,
+    },
+    /// Evict the stored `GET`s after a write succeeds
+    ///
+    /// Keys are built from query-less URLs and also match the same URL with
+    /// any query string.
+    Invalidate { keys: Vec<String> },
+}
+
+/// The parts of `Cache-Control` the cache acts on
+#[derive(Debug, Default, PartialEq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    max_age: Option<Duration>,
+}
+
+impl CacheControl {
+    fn parse(header: Option<&str>) -> Self {
+        let mut directives = Self::default();
+        for directive in header.unwrap_or_default().split(',') {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => directives.no_store = true,
+                "no-cache" => directives.no_cache = true,
+                "max-age" => {
+                    directives.max_age = value
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(Duration::from_secs);
+                }
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+/// Whether a response may be stored at all
+fn is_storable(headers: &HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+    let directives = CacheControl::parse(header(reqwest::header::CACHE_CONTROL));
+    let has_validator = headers.contains_key(reqwest::header::ETAG)
+        || headers.contains_key(reqwest::header::LAST_MODIFIED);
+
+    !directives.no_store
+        && header(reqwest::header::VARY) != Some("*")
+        && (has_validator || directives.max_age.is_some_and(|age| !age.is_zero()))
+}
+
+/// Key of a request: its URL, the identity it authenticates as and the API
+/// version it asks for in `version_header`
+///
+/// Credentials are hashed, so keys can be stored without leaking them.
+pub(crate) fn request_key(request: &reqwest::Request, version_header: Option<&str>) -> String {
+    key_for(request, version_header, request.url())
+}
+
+/// Key of a `GET` of `url` made with the headers of `request`
+fn key_for(request: &reqwest::Request, version_header: Option<&str>, url: &url::Url) -> String {
+    let headers = request.headers();
+    let identity = headers
+        .get(reqwest::header::AUTHORIZATION)
+        .map(|value| hex_digest(&String::from_utf8_lossy(value.as_bytes())))
+        .unwrap_or_else(|| "anonymous".to_string());
+    match version_header.and_then(|name| headers.get(name)) {
+        Some(version) => format!("{} {} {}", identity, String::from_utf8_lossy(version.as_bytes()), url),
+        None => format!("{} {}", identity, url),
+    }
+}
+
+/// Parent paths of `url` up to its resource collection, nearest first
+fn parent_urls(url: &url::Url) -> Vec<url::Url> {
+    let segments: Vec<&str> = url.path().split('/').filter(|s| !s.is_empty()).collect();
+    let group = crate::request::endpoint_group(url.path());
+    let Some(collection) = segments.iter().position(|segment| *segment == group) else {
+        return Vec::new();
+    };
+
+    (collection + 1..segments.len())
+        .rev()
+        .map(|end| {
+            let mut parent = url.clone();
+            parent.set_path(&format!("/{}", segments[..end].join("/")));
+            parent.set_query(None);
+            parent.set_fragment(None);
+            parent
+        })
+        .collect()
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn hex_digest(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(headers: &[(&'static str, &str)], body: &str) -> HttpResponse {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(HeaderName::from_static(name), HeaderValue::from_str(value).unwrap());
+        }
+        HttpResponse::new(
+            Method::GET,
+            url::Url::parse("https://api.stateset.io/api/v1/products/p1").unwrap(),
+            StatusCode::OK,
+            map,
+            body.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_cache_control_parsing() {
+        let directives = CacheControl::parse(Some("private, Max-Age=\"60\", no-cache"));
+        assert!(directives.no_cache);
+        assert!(!directives.no_store);
+        assert_eq!(directives.max_age, Some(Duration::from_secs(60)));
+
+        assert!(is_storable(response(&[("etag", "\"v1\"")], "").headers()));
+        assert!(is_storable(response(&[("cache-control", "max-age=30")], "").headers()));
+        assert!(!is_storable(response(&[("cache-control", "max-age=0")], "").headers()));
+        assert!(!is_storable(response(&[("etag", "\"v1\""), ("cache-control", "no-store")], "").headers()));
+    }
+
+    #[test]
+    fn test_freshness() {
+        let mut entry = CachedResponse::from_response(&response(&[("cache-control", "max-age=60")], "{}"));
+        assert!(entry.is_fresh());
+
+        entry.stored_at -= 61;
+        assert!(!entry.is_fresh());
+
+        let entry = CachedResponse::from_response(&response(
+            &[("cache-control", "max-age=60, no-cache"), ("etag", "\"v1\"")],
+            "{}",
+        ));
+        assert!(!entry.is_fresh());
+        assert_eq!(entry.etag(), Some("\"v1\""));
+    }
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2);
+        let entry = CachedResponse::from_response(&response(&[], "{}"));
+
+        cache.put("a", entry.clone());
+        cache.put("b", entry.clone());
+        assert!(cache.get("a").is_some());
+        cache.put("c", entry);
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("responses")).unwrap();
+        let entry = CachedResponse::from_response(&response(&[("etag", "\"v1\"")], "{\"id\":\"p1\"}\n"));
+
+        cache.put("key", entry.clone());
+        let reopened = DiskCache::new(dir.path().join("responses")).unwrap();
+        assert_eq!(reopened.get("key"), Some(entry.clone()));
+        assert_eq!(reopened.get("other"), None);
+
+        reopened.remove("key");
+        assert_eq!(cache.get("key"), None);
+
+        cache.put("orders?page=1", entry.clone());
+        cache.put("orders?page=2", entry.clone());
+        cache.put("products", entry.clone());
+        cache.remove_matching(&|key| key.starts_with("orders"));
+        assert_eq!(cache.get("orders?page=1"), None);
+        assert_eq!(cache.get("orders?page=2"), None);
+        assert_eq!(cache.get("products"), Some(entry));
+    }
+
+    #[test]
+    fn test_key_depends_on_identity() {
+        let mut request = reqwest::Request::new(
+            Method::GET,
+            url::Url::parse("https://api.stateset.io/api/v1/products/p1").unwrap(),
+        );
+        let anonymous = request_key(&request, None);
+
+        request
+            .headers_mut()
+            .insert(reqwest::header::AUTHORIZATION, HeaderValue::from_static("Bearer a"));
+        let first = request_key(&request, None);
+        request
+            .headers_mut()
+            .insert(reqwest::header::AUTHORIZATION, HeaderValue::from_static("Bearer b"));
+        let second = request_key(&request, None);
+
+        assert_ne!(anonymous, first);
+        assert_ne!(first, second);
+        assert!(!first.contains("Bearer"));
+
+        request
+            .headers_mut()
+            .insert("stateset-version", HeaderValue::from_static("2024-06-01"));
+        assert_eq!(request_key(&request, None), second);
+        assert_ne!(request_key(&request, Some("StateSet-Version")), second);
+    }
+
+    #[test]
+    fn test_parent_urls() {
+        let parents = |url: &str| -> Vec<String> {
+            parent_urls(&url::Url::parse(url).unwrap())
+                .into_iter()
+                .map(String::from)
+                .collect()
+        };
+        assert_eq!(
+            parents("https://api.stateset.io/api/v1/orders/ord_1/cancel?notify=true"),
+            [
+                "https://api.stateset.io/api/v1/orders/ord_1",
+                "https://api.stateset.io/api/v1/orders",
+            ]
+        );
+        assert!(parents("https://api.stateset.io/api/v1/orders").is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+//! Circuit breakers per host and resource group
+//!
+//! With [`Config::circuit_breaker`](stateset_core::Config) set, every attempt
+//! passes through the breaker of its host and resource group, named like
+//! `api.stateset.io/inventory`, so a failing inventory API does not stop
+//! orders from going through. Server errors, timeouts and network errors
+//! count as failures; any response below 500 counts as a success.
+//!
+//! After [`failure_threshold`](stateset_core::CircuitBreakerSettings)
+//! consecutive failures a breaker opens and fails attempts fast with
+//! [`Error::CircuitOpen`], whose `retry_after` is the time left until it
+//! half-opens. Rejections are never retried. A half-open breaker lets `half_open_probes` attempts
+//! through, rejecting the rest; it closes once all of them succeed and opens
+//! again on the first failure.
+//!
+//! Breakers are shared by all clones of a client. State changes are reported
+//! to callbacks, streams and attached [`Metrics`] registries.
+
+use crate::failover::Outcome;
+use crate::metrics::Metrics;
+use futures::channel::mpsc;
+use stateset_core::{CircuitBreakerSettings, Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use url::Url;
+
+/// State of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests pass
+    Closed = 0,
+    /// Requests fail fast until the recovery timeout has passed
+    Open = 1,
+    /// Requests pass to probe whether the service has recovered
+    HalfOpen = 2,
+}
+
+/// A circuit breaker moving from one state to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateChange {
+    /// Breaker name, `<host>/<resource group>`
+    pub circuit: String,
+    /// State before the change
+    pub from: CircuitState,
+    /// State after the change
+    pub to: CircuitState,
+}
+
+type Listener = Arc<dyn Fn(&StateChange) + Send + Sync>;
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Instant,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+impl Breaker {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: Instant::now(),
+            probes_in_flight: 0,
+            probe_successes: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Observers {
+    listeners: Vec<Listener>,
+    streams: Vec<mpsc::UnboundedSender<StateChange>>,
+    metrics: Vec<Metrics>,
+}
+
+/// Breakers of one client and the observers of their state changes
+pub(crate) struct CircuitBreakers {
+    settings: CircuitBreakerSettings,
+    breakers: Mutex<HashMap<String, Breaker>>,
+    observers: Mutex<Observers>,
+}
+
+/// Permission to send one attempt through a breaker
+///
+/// Dropping it without [`record`](Self::record)ing an outcome, e.g. when the
+/// attempt is cancelled, frees its probe slot.
+pub(crate) struct Admission<'a> {
+    breakers: &'a CircuitBreakers,
+    circuit: String,
+    probe: bool,
+}
+
+impl Admission<'_> {
+    /// Record how the attempt went
+    pub(crate) fn record(mut self, outcome: Outcome) {
+        let probe = std::mem::take(&mut self.probe);
+        self.breakers.record(&self.circuit, probe, outcome);
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            if let Some(breaker) = lock(&self.breakers.breakers).get_mut(&self.circuit) {
+                breaker.probes_in_flight = breaker.probes_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Name of the breaker guarding `url`
+pub(crate) fn circuit_name(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    let group = crate::request::endpoint_group(url.path());
+    match url.port() {
+        Some(port) => format!("{}:{}/{}", host, port, group),
+        None => format!("{}/{}", host, group),
+    }
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new(settings: CircuitBreakerSettings) -> Self {
+        Self {
+            settings,
+            breakers: Mutex::new(HashMap::new()),
+            observers: Mutex::new(Observers::default()),
+        }
+    }
+
+    /// Let an attempt to `url` through, or fail it fast
+    pub(crate) fn admit(&self, url: &Url) -> Result<Admission<'_>> {
+        let circuit = circuit_name(url);
+        let mut changes = Vec::new();
+        let result = {
+            let mut breakers = lock(&self.breakers);
+            let breaker = breakers.entry(circuit.clone()).or_insert_with(|| {
+                changes.push(None);
+                Breaker::new()
+            });
+
+            let remaining = self.settings.recovery_timeout.saturating_sub(breaker.opened_at.elapsed());
+            if breaker.state == CircuitState::Open && remaining.is_zero() {
+                changes.push(Some(transition(&circuit, breaker, CircuitState::HalfOpen)));
+            }
+
+            match breaker.state {
+                CircuitState::Open => Err(Error::CircuitOpen {
+                    circuit: circuit.clone(),
+                    message: "failing fast until the recovery timeout passes".to_string(),
+                    retry_after: Some(remaining),
+                }),
+                CircuitState::HalfOpen
+                    if breaker.probes_in_flight + breaker.probe_successes >= self.settings.half_open_probes =>
+                {
+                    Err(Error::CircuitOpen {
+                        circuit: circuit.clone(),
+                        message: "half-open and probing".to_string(),
+                        retry_after: None,
+                    })
+                }
+                CircuitState::HalfOpen => {
+                    breaker.probes_in_flight += 1;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        };
+
+        self.notify(&circuit, changes);
+        result.map(|probe| Admission {
+            breakers: self,
+            circuit,
+            probe,
+        })
+    }
+
+    fn record(&self, circuit: &str, probe: bool, outcome: Outcome) {
+        let change = {
+            let mut breakers = lock(&self.breakers);
+            let Some(breaker) = breakers.get_mut(circuit) else {
+                return;
+            };
+            if probe {
+                breaker.probes_in_flight = breaker.probes_in_flight.saturating_sub(1);
+            }
+
+            let success = outcome == Outcome::Success;
+            match breaker.state {
+                CircuitState::Closed if success => {
+                    breaker.consecutive_failures = 0;
+                    None
+                }
+                CircuitState::Closed => {
+                    breaker.consecutive_failures += 1;
+                    (breaker.consecutive_failures >= self.settings.failure_threshold)
+                        .then(|| transition(circuit, breaker, CircuitState::Open))
+                }
+                // Only probes decide; late answers to earlier attempts do not
+                CircuitState::HalfOpen if probe && success => {
+                    breaker.probe_successes += 1;
+                    (breaker.probe_successes >= self.settings.half_open_probes)
+                        .then(|| transition(circuit, breaker, CircuitState::Closed))
+                }
+                CircuitState::HalfOpen if probe => Some(transition(circuit, breaker, CircuitState::Open)),
+                _ => None,
+            }
+        };
+
+        if let Some(change) = change {
+            self.notify(circuit, vec![Some(change)]);
+        }
+    }
+
+    /// Report new breakers (`None`) and state changes to every observer
+    fn notify(&self, circuit: &str, changes: Vec<Option<StateChange>>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        let mut observers = lock(&self.observers);
+        for change in &changes {
+            let state = change.as_ref().map_or(CircuitState::Closed, |change| change.to);
+            for metrics in &observers.metrics {
+                metrics.record_circuit_state(circuit, state);
+            }
+        }
+
+        let changes: Vec<_> = changes.into_iter().flatten().collect();
+        for change in &changes {
+            match change.to {
+                CircuitState::Open => log::warn!("Circuit breaker {} opened", change.circuit),
+                _ => log::info!("Circuit breaker {} is now {:?}", change.circuit, change.to),
+            }
+            observers
+                .streams
+                .retain(|stream| stream.unbounded_send(change.clone()).is_ok());
+        }
+
+        // Call listeners without holding the lock, so they may subscribe too
+        let listeners = observers.listeners.clone();
+        drop(observers);
+        for change in &changes {
+            for listener in &listeners {
+                listener(change);
+            }
+        }
+    }
+
+    /// Call `listener` on every state change
+    pub(crate) fn on_state_change(&self, listener: Listener) {
+        lock(&self.observers).listeners.push(listener);
+    }
+
+    /// Stream of all state changes from now on
+    pub(crate) fn subscribe(&self) -> mpsc::UnboundedReceiver<StateChange> {
+        let (sender, receiver) = mpsc::unbounded();
+        lock(&self.observers).streams.push(sender);
+        receiver
+    }
+
+    /// Report the state of every breaker, now and after each change, to
+    /// `metrics`
+    pub(crate) fn report_to(&self, metrics: &Metrics) {
+        let states = self.states();
+        let mut observers = lock(&self.observers);
+        if observers.metrics.iter().any(|registered| registered.same_registry(metrics)) {
+            return;
+        }
+        for (circuit, state) in states {
+            metrics.record_circuit_state(&circuit, state);
+        }
+        observers.metrics.push(metrics.clone());
+    }
+
+    /// Current state of every breaker
+    pub(crate) fn states(&self) -> BTreeMap<String, CircuitState> {
+        lock(&self.breakers)
+            .iter()
+            .map(|(circuit, breaker)| (circuit.clone(), breaker.state))
+            .collect()
+    }
+}
+
+fn transition(circuit: &str, breaker: &mut Breaker, to: CircuitState) -> StateChange {
+    let from = breaker.state;
+    breaker.state = to;
+    breaker.consecutive_failures = 0;
+    breaker.probe_successes = 0;
+    if to == CircuitState::Open {
+        breaker.opened_at = Instant::now();
+    }
+    StateChange {
+        circuit: circuit.to_string(),
+        from,
+        to,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn breakers(recovery_timeout: Duration, half_open_probes: u32) -> CircuitBreakers {
+        CircuitBreakers::new(CircuitBreakerSettings {
+            failure_threshold: 2,
+            recovery_timeout,
+            half_open_probes,
+        })
+    }
+
+    #[test]
+    fn test_breakers_are_keyed_by_host_and_resource() {
+        let breakers = breakers(Duration::from_secs(60), 1);
+        let inventory: Url = "https://api.stateset.io/api/v1/inventory/sku_1".parse().unwrap();
+        let orders: Url = "https://api.stateset.io/api/v1/orders".parse().unwrap();
+
+        breakers.admit(&inventory).unwrap().record(Outcome::Failure);
+        breakers.admit(&inventory).unwrap().record(Outcome::Unreachable);
+        let error = breakers.admit(&inventory).err().unwrap();
+        assert!(matches!(error, Error::CircuitOpen { retry_after: Some(_), .. }));
+        assert!(!error.is_retryable());
+        assert!(breakers.admit(&orders).is_ok());
+
+        let states = breakers.states();
+        assert_eq!(states["api.stateset.io/inventory"], CircuitState::Open);
+        assert_eq!(states["api.stateset.io/orders"], CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_limits_probes() {
+        let breakers = breakers(Duration::from_millis(10), 2);
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let seen = changes.clone();
+        breakers.on_state_change(Arc::new(move |change: &StateChange| {
+            seen.lock().unwrap().push(change.to);
+        }));
+        let url: Url = "http://localhost:8080/api/v1/inventory".parse().unwrap();
+
+        breakers.admit(&url).unwrap().record(Outcome::Failure);
+        breakers.admit(&url).unwrap().record(Outcome::Failure);
+        std::thread::sleep(Duration::from_millis(20));
+
+        let first = breakers.admit(&url).unwrap();
+        let second = breakers.admit(&url).unwrap();
+        assert!(breakers.admit(&url).is_err());
+
+        // A cancelled probe frees its slot
+        drop(second);
+        let second = breakers.admit(&url).unwrap();
+        first.record(Outcome::Success);
+        assert_eq!(breakers.states()["localhost:8080/inventory"], CircuitState::HalfOpen);
+        second.record(Outcome::Success);
+        assert_eq!(breakers.states()["localhost:8080/inventory"], CircuitState::Closed);
+
+        // A failed probe opens the breaker again
+        breakers.admit(&url).unwrap().record(Outcome::Failure);
+        breakers.admit(&url).unwrap().record(Outcome::Failure);
+        std::thread::sleep(Duration::from_millis(20));
+        breakers.admit(&url).unwrap().record(Outcome::Failure);
+        assert!(breakers.admit(&url).is_err());
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Closed,
+                CircuitState::Open,
+                CircuitState::HalfOpen,
+                CircuitState::Open,
+            ]
+        );
+    }
+}
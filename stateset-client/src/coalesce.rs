@@ -0,0 +1,194 @@
+//! Single-flight coalescing of identical in-flight `GET`s
+//!
+//! With a [`RequestCoalescer`] attached through
+//! [`Client::with_coalescing`](crate::Client::with_coalescing), concurrent
+//! `GET`s of the same URL by the same credentials share one request: the
+//! first caller sends it and every caller that arrives while it is in
+//! flight receives a copy of its result, success or error. Nothing is kept
+//! once the request completes.
+//!
+//! If the caller that sent the request is cancelled, waiting callers send
+//! the request themselves.
+
+use crate::response::{ApiResponse, HttpResponse};
+use futures::channel::oneshot;
+use stateset_core::Result;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+type Outcome = Result<ApiResponse<HttpResponse>>;
+
+/// Coalescing counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoalescingStats {
+    /// Requests sent on behalf of one or more callers
+    pub requests: u64,
+    /// Calls answered with the result of another caller's request
+    pub deduplicated: u64,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    in_flight: Mutex<HashMap<String, Vec<oneshot::Sender<Outcome>>>>,
+    requests: AtomicU64,
+    deduplicated: AtomicU64,
+}
+
+/// Shares in-flight `GET`s between concurrent callers
+///
+/// Clones share in-flight requests and statistics. Only share a coalescer
+/// between clients whose requests to the same URL are interchangeable.
+#[derive(Debug, Clone, Default)]
+pub struct RequestCoalescer {
+    shared: Arc<Shared>,
+}
+
+impl RequestCoalescer {
+    /// Create a coalescer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests sent and calls deduplicated so far
+    pub fn stats(&self) -> CoalescingStats {
+        CoalescingStats {
+            requests: self.shared.requests.load(Ordering::Relaxed),
+            deduplicated: self.shared.deduplicated.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Vec<oneshot::Sender<Outcome>>>> {
+        match self.shared.in_flight.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Run `request` unless an identical one is in flight, then share its result
+    pub(crate) async fn run<F>(&self, key: String, request: F) -> Outcome
+    where
+        F: Future<Output = Outcome>,
+    {
+        let waiting = {
+            let mut in_flight = self.lock();
+            match in_flight.get_mut(&key) {
+                Some(waiters) => {
+                    let (sender, receiver) = oneshot::channel();
+                    waiters.push(sender);
+                    Some(receiver)
+                }
+                None => {
+                    in_flight.insert(key.clone(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(receiver) = waiting {
+            match receiver.await {
+                Ok(outcome) => {
+                    self.shared.deduplicated.fetch_add(1, Ordering::Relaxed);
+                    return outcome;
+                }
+                Err(oneshot::Canceled) => {
+                    log::debug!("Coalesced request for {} was cancelled, sending it again", key);
+                    self.shared.requests.fetch_add(1, Ordering::Relaxed);
+                    return request.await;
+                }
+            }
+        }
+
+        self.shared.requests.fetch_add(1, Ordering::Relaxed);
+        let flight = Flight {
+            coalescer: self,
+            key: Some(key),
+        };
+        let outcome = request.await;
+        for waiter in flight.land() {
+            let _ = waiter.send(outcome.clone());
+        }
+        outcome
+    }
+}
+
+/// An in-flight request; dropping it early releases the waiting callers
+struct Flight<'a> {
+    coalescer: &'a RequestCoalescer,
+    key: Option<String>,
+}
+
+impl Flight<'_> {
+    /// Remove the request from the in-flight set and return its waiters
+    fn land(mut self) -> Vec<oneshot::Sender<Outcome>> {
+        self.key
+            .take()
+            .and_then(|key| self.coalescer.lock().remove(&key))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            // Dropping the senders wakes the waiters with `Canceled`
+            self.coalescer.lock().remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::ResponseMetadata;
+    use futures::FutureExt;
+    use reqwest::{header::HeaderMap, Method, StatusCode};
+    use std::time::Duration;
+
+    fn outcome(body: &str) -> Outcome {
+        let response = HttpResponse::new(
+            Method::GET,
+            url::Url::parse("https://api.stateset.io/api/v1/inventory/sku_1").unwrap(),
+            StatusCode::OK,
+            HeaderMap::new(),
+            body.to_string(),
+        );
+        let metadata = ResponseMetadata {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            request_id: None,
+            attempts: 1,
+            latency: Duration::ZERO,
+            hedges: 0,
+            hedge_won: false,
+            from_cache: false,
+            endpoint: None,
+        };
+        Ok(ApiResponse::new(response, metadata))
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_leader_releases_waiters() {
+        let coalescer = RequestCoalescer::new();
+        let (release, gate) = oneshot::channel::<()>();
+
+        // The leader never finishes and is dropped while a follower waits
+        let mut leader = Box::pin(coalescer.run("k".to_string(), async move {
+            let _ = gate.await;
+            outcome("leader")
+        }));
+        assert!((&mut leader).now_or_never().is_none());
+
+        let follower = coalescer.run("k".to_string(), async { outcome("follower") });
+        let (follower, ()) = futures::join!(follower, async move {
+            tokio::task::yield_now().await;
+            drop(leader);
+            drop(release);
+        });
+
+        assert_eq!(follower.unwrap().data().text(), "follower");
+        assert_eq!(coalescer.stats(), CoalescingStats { requests: 2, deduplicated: 0 });
+        assert!(coalescer.lock().is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+//! Request body compression
+//!
+//! Response decompression (gzip, brotli, zstd) is negotiated by the HTTP
+//! client whenever [`Config::compression`] is enabled. Request bodies are
+//! gzipped only when [`Config::request_compression_threshold`] is set and the
+//! body is at least that large, the endpoint group is not exempt, and the
+//! compressed body is actually smaller.
+
+use flate2::{write::GzEncoder, Compression};
+use reqwest::header::{HeaderValue, CONTENT_ENCODING};
+use stateset_core::Config;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bytes saved by request compression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionStats {
+    /// Number of request bodies sent compressed
+    pub requests_compressed: u64,
+    /// Size of those bodies before compression
+    pub bytes_before: u64,
+    /// Size of those bodies after compression
+    pub bytes_after: u64,
+}
+
+impl CompressionStats {
+    /// Bytes kept off the wire
+    pub fn bytes_saved(&self) -> u64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+}
+
+/// Compresses eligible request bodies and tracks the savings
+#[derive(Debug, Default)]
+pub(crate) struct RequestCompressor {
+    threshold: Option<usize>,
+    exempt: HashSet<String>,
+    requests_compressed: AtomicU64,
+    bytes_before: AtomicU64,
+    bytes_after: AtomicU64,
+}
+
+impl RequestCompressor {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            threshold: config
+                .request_compression_threshold
+                .filter(|_| config.compression),
+            exempt: config.request_compression_exempt.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Gzip the request body in place if it qualifies
+    ///
+    /// `force` overrides the threshold and group exemptions: `Some(false)`
+    /// never compresses, `Some(true)` compresses any buffered body.
+    pub(crate) fn compress(&self, request: &mut reqwest::Request, force: Option<bool>) {
+        if request.headers().contains_key(CONTENT_ENCODING) {
+            return;
+        }
+        let Some(body) = request.body().and_then(|b| b.as_bytes()) else {
+            return;
+        };
+
+        let eligible = match force {
+            Some(force) => force,
+            None => self.threshold.is_some_and(|threshold| body.len() >= threshold)
+                && !self
+                    .exempt
+                    .contains(crate::request::endpoint_group(request.url().path())),
+        };
+        if !eligible {
+            return;
+        }
+
+        let Some(compressed) = gzip(body).filter(|c| c.len() < body.len()) else {
+            return;
+        };
+
+        self.requests_compressed.fetch_add(1, Ordering::Relaxed);
+        self.bytes_before.fetch_add(body.len() as u64, Ordering::Relaxed);
+        self.bytes_after.fetch_add(compressed.len() as u64, Ordering::Relaxed);
+
+        *request.body_mut() = Some(compressed.into());
+        request
+            .headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    }
+
+    pub(crate) fn stats(&self) -> CompressionStats {
+        CompressionStats {
+            requests_compressed: self.requests_compressed.load(Ordering::Relaxed),
+            bytes_before: self.bytes_before.load(Ordering::Relaxed),
+            bytes_after: self.bytes_after.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn gzip(data: &[u8]) -> Option<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 2), Compression::default());
+    encoder.write_all(data).ok()?;
+    encoder.finish().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+    use std::io::Read;
+
+    fn request(path: &str, body: &str) -> reqwest::Request {
+        let url = url::Url::parse("https://api.stateset.io").unwrap().join(path).unwrap();
+        let mut request = reqwest::Request::new(Method::POST, url);
+        *request.body_mut() = Some(body.to_string().into());
+        request
+    }
+
+    fn compressor(threshold: usize) -> RequestCompressor {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .request_compression_threshold(threshold)
+            .request_compression_exempt("inventory")
+            .build()
+            .unwrap();
+        RequestCompressor::from_config(&config)
+    }
+
+    #[test]
+    fn test_compresses_large_bodies() {
+        let compressor = compressor(64);
+        let body = r#"{"sku":"WIDGET-001","quantity":1}"#.repeat(20);
+        let mut request = request("/api/v1/orders/batch", &body);
+
+        compressor.compress(&mut request, None);
+
+        assert_eq!(request.headers()[CONTENT_ENCODING], "gzip");
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(request.body().unwrap().as_bytes().unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let stats = compressor.stats();
+        assert_eq!(stats.requests_compressed, 1);
+        assert_eq!(stats.bytes_before, body.len() as u64);
+        assert!(stats.bytes_saved() > 0);
+    }
+
+    #[test]
+    fn test_skips_small_and_exempt_bodies() {
+        let compressor = compressor(64);
+        let large = "x".repeat(1024);
+
+        let mut small = request("/api/v1/orders", "{}");
+        compressor.compress(&mut small, None);
+        assert!(!small.headers().contains_key(CONTENT_ENCODING));
+
+        let mut exempt = request("/api/v1/inventory/adjustments", &large);
+        compressor.compress(&mut exempt, None);
+        assert!(!exempt.headers().contains_key(CONTENT_ENCODING));
+
+        let mut opted_out = request("/api/v1/orders/batch", &large);
+        compressor.compress(&mut opted_out, Some(false));
+        assert!(!opted_out.headers().contains_key(CONTENT_ENCODING));
+
+        let mut forced = request("/api/v1/inventory/adjustments", &large);
+        compressor.compress(&mut forced, Some(true));
+        assert_eq!(forced.headers()[CONTENT_ENCODING], "gzip");
+    }
+
+    #[test]
+    fn test_disabled_without_compression() {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .compression(false)
+            .request_compression_threshold(1)
+            .build()
+            .unwrap();
+        let compressor = RequestCompressor::from_config(&config);
+
+        let mut request = request("/api/v1/orders/batch", &"x".repeat(1024));
+        compressor.compress(&mut request, None);
+        assert!(!request.headers().contains_key(CONTENT_ENCODING));
+    }
+}
@@ -0,0 +1,294 @@
+//! Client-wide concurrency limit with priority classes
+//!
+//! Every request waits for a permit before it is sent, so no more than
+//! [`PoolSettings::max_total_connections`](stateset_core::PoolSettings)
+//! requests are in flight across all clones of a client. Requests are
+//! [`Priority::Interactive`] unless marked [`Priority::Background`] with
+//! [`RequestOptions::priority`](crate::request::RequestOptions::priority):
+//!
+//! - waiting interactive requests are always admitted before background ones;
+//! - background requests never hold more than
+//!   [`Config::background_concurrency`](stateset_core::Config) permits, so
+//!   bulk work such as nightly exports leaves room for interactive traffic.
+//!
+//! Permits cover sending the request and receiving the response head; the
+//! body of a streamed download is read after the permit is released.
+
+use futures::channel::oneshot;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Scheduling class of a request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Latency-sensitive traffic, admitted first
+    #[default]
+    Interactive,
+    /// Bulk traffic limited to a share of the permits
+    Background,
+}
+
+impl Priority {
+    fn index(self) -> usize {
+        match self {
+            Priority::Interactive => 0,
+            Priority::Background => 1,
+        }
+    }
+}
+
+/// Queue metrics of one priority class
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityStats {
+    /// Requests currently holding a permit
+    pub in_flight: usize,
+    /// Requests currently waiting for a permit
+    pub queued: usize,
+    /// Permits granted so far
+    pub admitted: u64,
+    /// Total time spent waiting for permits
+    pub total_wait: Duration,
+    /// Longest single wait
+    pub max_wait: Duration,
+}
+
+impl PriorityStats {
+    /// Average time spent waiting for a permit
+    pub fn average_wait(&self) -> Duration {
+        match self.admitted {
+            0 => Duration::ZERO,
+            admitted => self.total_wait / admitted.min(u64::from(u32::MAX)) as u32,
+        }
+    }
+}
+
+/// Queue depth and wait time per priority class
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConcurrencyStats {
+    /// Maximum requests in flight
+    pub limit: usize,
+    /// Maximum background requests in flight
+    pub background_limit: usize,
+    /// Interactive traffic
+    pub interactive: PriorityStats,
+    /// Background traffic
+    pub background: PriorityStats,
+}
+
+impl ConcurrencyStats {
+    /// Requests waiting for a permit across both classes
+    pub fn queue_depth(&self) -> usize {
+        self.interactive.queued + self.background.queued
+    }
+}
+
+struct Waiter {
+    enqueued_at: Instant,
+    sender: oneshot::Sender<Permit>,
+}
+
+#[derive(Default)]
+struct State {
+    queues: [VecDeque<Waiter>; 2],
+    stats: [PriorityStats; 2],
+}
+
+impl State {
+    fn in_flight(&self) -> usize {
+        self.stats.iter().map(|stats| stats.in_flight).sum()
+    }
+}
+
+/// Hands out permits to send requests
+pub(crate) struct ConcurrencyLimiter {
+    limit: usize,
+    background_limit: usize,
+    state: Mutex<State>,
+}
+
+impl ConcurrencyLimiter {
+    /// Allow `limit` requests in flight, at most `background_limit` of them
+    /// in the background class
+    pub(crate) fn new(limit: usize, background_limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            limit,
+            background_limit: background_limit.clamp(1, limit),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn has_room(&self, state: &State, priority: Priority) -> bool {
+        state.in_flight() < self.limit
+            && (priority == Priority::Interactive
+                || state.stats[Priority::Background.index()].in_flight < self.background_limit)
+    }
+
+    /// Record a granted permit
+    fn admit(self: &Arc<Self>, state: &mut State, priority: Priority, waited: Duration) -> Permit {
+        let stats = &mut state.stats[priority.index()];
+        stats.in_flight += 1;
+        stats.admitted += 1;
+        stats.total_wait += waited;
+        stats.max_wait = stats.max_wait.max(waited);
+
+        Permit {
+            limiter: self.clone(),
+            priority,
+        }
+    }
+
+    /// Wait for a permit to send a request
+    pub(crate) async fn acquire(self: &Arc<Self>, priority: Priority) -> Permit {
+        let receiver = {
+            let mut state = self.lock();
+            // Nobody may overtake a waiter of the same or a higher class
+            let overtakes = state.queues[..=priority.index()]
+                .iter()
+                .any(|queue| !queue.is_empty());
+            if !overtakes && self.has_room(&state, priority) {
+                return self.admit(&mut state, priority, Duration::ZERO);
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.queues[priority.index()].push_back(Waiter {
+                enqueued_at: Instant::now(),
+                sender,
+            });
+            state.stats[priority.index()].queued += 1;
+            receiver
+        };
+
+        match receiver.await {
+            Ok(permit) => permit,
+            // Waiters leave the queue only through `release`, which always
+            // sends them a permit
+            Err(oneshot::Canceled) => unreachable!("concurrency limiter dropped a waiter"),
+        }
+    }
+
+    /// Return a permit and admit the next waiters
+    fn release(self: &Arc<Self>, priority: Priority) {
+        let mut grants = Vec::new();
+        {
+            let mut state = self.lock();
+            state.stats[priority.index()].in_flight -= 1;
+
+            for priority in [Priority::Interactive, Priority::Background] {
+                while self.has_room(&state, priority) {
+                    let Some(waiter) = state.queues[priority.index()].pop_front() else {
+                        break;
+                    };
+                    state.stats[priority.index()].queued -= 1;
+                    let waited = waiter.enqueued_at.elapsed();
+                    let permit = self.admit(&mut state, priority, waited);
+                    grants.push((waiter.sender, permit));
+                }
+            }
+        }
+
+        // Send outside the lock: a waiter that gave up drops its permit,
+        // which releases it again
+        for (sender, permit) in grants {
+            let _ = sender.send(permit);
+        }
+    }
+
+    /// Current queue metrics
+    pub(crate) fn stats(&self) -> ConcurrencyStats {
+        let state = self.lock();
+        ConcurrencyStats {
+            limit: self.limit,
+            background_limit: self.background_limit,
+            interactive: state.stats[Priority::Interactive.index()],
+            background: state.stats[Priority::Background.index()],
+        }
+    }
+}
+
+impl std::fmt::Debug for ConcurrencyLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrencyLimiter")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// Permission to have one request in flight
+pub(crate) struct Permit {
+    limiter: Arc<ConcurrencyLimiter>,
+    priority: Priority,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.limiter.release(self.priority);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn test_interactive_requests_jump_the_queue() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(1, 1));
+        let held = limiter.acquire(Priority::Background).await;
+
+        let mut background = Box::pin(limiter.acquire(Priority::Background));
+        let mut interactive = Box::pin(limiter.acquire(Priority::Interactive));
+        assert!((&mut background).now_or_never().is_none());
+        assert!((&mut interactive).now_or_never().is_none());
+        assert_eq!(limiter.stats().queue_depth(), 2);
+
+        drop(held);
+        let permit = (&mut interactive).now_or_never().expect("interactive admitted first");
+        assert!((&mut background).now_or_never().is_none());
+
+        drop(permit);
+        let _permit = background.now_or_never().expect("background admitted next");
+        let stats = limiter.stats();
+        assert_eq!(stats.queue_depth(), 0);
+        assert_eq!(stats.interactive.admitted, 1);
+        assert_eq!(stats.background.admitted, 2);
+        assert_eq!(stats.background.in_flight, 1);
+    }
+
+    #[tokio::test]
+    async fn test_background_share_is_capped() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(3, 1));
+        let _background = limiter.acquire(Priority::Background).await;
+
+        let mut second = Box::pin(limiter.acquire(Priority::Background));
+        assert!((&mut second).now_or_never().is_none());
+
+        // Interactive requests still find room
+        let _a = limiter.acquire(Priority::Interactive).now_or_never().unwrap();
+        let _b = limiter.acquire(Priority::Interactive).now_or_never().unwrap();
+        assert!(limiter.acquire(Priority::Interactive).now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_waiter_returns_its_permit() {
+        let limiter = Arc::new(ConcurrencyLimiter::new(1, 1));
+        let held = limiter.acquire(Priority::Interactive).await;
+
+        let mut abandoned = Box::pin(limiter.acquire(Priority::Interactive));
+        assert!((&mut abandoned).now_or_never().is_none());
+        drop(abandoned);
+        drop(held);
+
+        let stats = limiter.stats();
+        assert_eq!(stats.interactive.in_flight, 0);
+        assert!(limiter.acquire(Priority::Interactive).now_or_never().is_some());
+    }
+}
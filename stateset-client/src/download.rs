@@ -0,0 +1,338 @@
+//! Streaming downloads of binary content
+//!
+//! Exports, labels and documents can be large and are not JSON, so they are
+//! returned as a [`Download`]: the response headers plus a stream of body
+//! chunks that is never buffered as a whole. Downloads can be written to any
+//! `AsyncWrite` with progress reporting, and [`Client::download_to`] resumes
+//! interrupted transfers with HTTP range requests.
+//!
+//! [`Client::download_to`]: crate::Client::download_to
+
+use crate::response::HttpResponse;
+use bytes::{Bytes, BytesMut};
+use futures::io::{AsyncWrite, AsyncWriteExt};
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use stateset_core::{Error, Result};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use url::Url;
+
+/// Progress of a download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// Bytes received so far, including any resumed offset
+    pub downloaded: u64,
+    /// Total size of the content, if known
+    pub total: Option<u64>,
+}
+
+/// A streaming response body with its headers
+pub struct Download {
+    status: StatusCode,
+    headers: HeaderMap,
+    url: Url,
+    offset: u64,
+    body: BoxStream<'static, Result<Bytes>>,
+}
+
+impl std::fmt::Debug for Download {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Download")
+            .field("status", &self.status)
+            .field("url", &self.url.as_str())
+            .field("content_type", &self.content_type())
+            .field("content_length", &self.content_length())
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl Download {
+    /// Create a download from response parts and a body stream
+    pub fn new(
+        status: StatusCode,
+        headers: HeaderMap,
+        url: Url,
+        body: impl Stream<Item = Result<Bytes>> + Send + 'static,
+    ) -> Self {
+        let offset = if status == StatusCode::PARTIAL_CONTENT {
+            content_range(&headers).map(|(start, _)| start).unwrap_or(0)
+        } else {
+            0
+        };
+
+        Self {
+            status,
+            headers,
+            url,
+            offset,
+            body: body.boxed(),
+        }
+    }
+
+    /// HTTP status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub(crate) fn set_headers(&mut self, headers: HeaderMap) {
+        self.headers = headers;
+    }
+
+    /// Final URL of the response
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// MIME type of the content, without parameters
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+            .and_then(|v| v.split(';').next())
+            .map(str::trim)
+    }
+
+    /// Length of this response body in bytes, if known
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("content-length").and_then(|v| v.parse().ok())
+    }
+
+    /// Size of the complete content, accounting for partial responses
+    pub fn total_size(&self) -> Option<u64> {
+        if self.is_partial() {
+            content_range(&self.headers).and_then(|(_, total)| total)
+        } else {
+            self.content_length()
+        }
+    }
+
+    /// File name suggested by the `Content-Disposition` header
+    pub fn filename(&self) -> Option<String> {
+        self.header("content-disposition").and_then(parse_filename)
+    }
+
+    /// Byte offset of the first chunk within the complete content
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Whether this is a partial (range) response
+    pub fn is_partial(&self) -> bool {
+        self.status == StatusCode::PARTIAL_CONTENT
+    }
+
+    /// Buffer the whole body in memory
+    pub async fn bytes(mut self) -> Result<Bytes> {
+        let mut buffer = BytesMut::with_capacity(self.content_length().unwrap_or(0) as usize);
+        while let Some(chunk) = self.body.next().await {
+            buffer.extend_from_slice(&chunk?);
+        }
+        Ok(buffer.freeze())
+    }
+
+    /// Buffer the body into a [`HttpResponse`]
+    pub(crate) async fn into_http_response(self, method: Method) -> Result<HttpResponse> {
+        let status = self.status;
+        let headers = self.headers.clone();
+        let url = self.url.clone();
+        let body = self.bytes().await?;
+        Ok(HttpResponse::new(method, url, status, headers, body))
+    }
+
+    /// Write the body to `writer`, reporting progress after every chunk
+    ///
+    /// Returns the number of bytes written.
+    pub async fn write_to<W, F>(self, writer: &mut W, mut progress: F) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+        F: FnMut(DownloadProgress),
+    {
+        let start = self.offset;
+        let mut downloaded = start;
+        self.copy_to(writer, &mut progress, &mut downloaded).await?;
+        Ok(downloaded - start)
+    }
+
+    /// Copy the body to `writer`, tracking the absolute position in `downloaded`
+    ///
+    /// Errors reading the body are returned as retryable network errors;
+    /// errors writing to `writer` are not retryable.
+    pub(crate) async fn copy_to<W, F>(
+        mut self,
+        writer: &mut W,
+        progress: &mut F,
+        downloaded: &mut u64,
+    ) -> Result<()>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+        F: FnMut(DownloadProgress),
+    {
+        let total = self.total_size();
+
+        while let Some(chunk) = self.body.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await.map_err(write_error)?;
+            *downloaded += chunk.len() as u64;
+            progress(DownloadProgress {
+                downloaded: *downloaded,
+                total,
+            });
+        }
+
+        writer.flush().await.map_err(write_error)
+    }
+}
+
+impl Stream for Download {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.body.poll_next_unpin(cx)
+    }
+}
+
+impl From<HttpResponse> for Download {
+    fn from(response: HttpResponse) -> Self {
+        let body = response.body().clone();
+        Self::new(
+            response.status(),
+            response.headers().clone(),
+            response.url().clone(),
+            stream::once(async move { Ok(body) }),
+        )
+    }
+}
+
+fn write_error(error: std::io::Error) -> Error {
+    Error::Other(format!("Failed to write download: {}", error))
+}
+
+/// Parse `Content-Range: bytes <start>-<end>/<total>`
+fn content_range(headers: &HeaderMap) -> Option<(u64, Option<u64>)> {
+    let value = headers.get("content-range")?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()))
+}
+
+/// Extract the file name from a `Content-Disposition` value
+fn parse_filename(value: &str) -> Option<String> {
+    let params = value.split(';').skip(1).filter_map(|p| p.split_once('='));
+    let mut plain = None;
+
+    for (key, value) in params {
+        match key.trim().to_ascii_lowercase().as_str() {
+            // RFC 5987 extended value: charset'language'percent-encoded
+            "filename*" => {
+                let encoded = value.trim().splitn(3, '\'').nth(2)?;
+                if let Ok(decoded) = urlencoding::decode(encoded) {
+                    return Some(decoded.into_owned());
+                }
+            }
+            "filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn download(status: StatusCode, headers: &[(&'static str, &str)], chunks: Vec<&'static str>) -> Download {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(*name, value.parse().unwrap());
+        }
+        Download::new(
+            status,
+            map,
+            Url::parse("https://api.stateset.io/api/v1/analytics/reports/1/export").unwrap(),
+            stream::iter(chunks.into_iter().map(|c| Ok(Bytes::from(c)))),
+        )
+    }
+
+    #[test]
+    fn test_headers() {
+        let download = download(
+            StatusCode::OK,
+            &[
+                ("content-type", "text/csv; charset=utf-8"),
+                ("content-length", "11"),
+                ("content-disposition", "attachment; filename=\"report.csv\""),
+            ],
+            vec![],
+        );
+        assert_eq!(download.content_type(), Some("text/csv"));
+        assert_eq!(download.content_length(), Some(11));
+        assert_eq!(download.total_size(), Some(11));
+        assert_eq!(download.filename().as_deref(), Some("report.csv"));
+        assert_eq!(download.offset(), 0);
+
+        let download = download_partial();
+        assert!(download.is_partial());
+        assert_eq!(download.offset(), 5);
+        assert_eq!(download.total_size(), Some(11));
+    }
+
+    fn download_partial() -> Download {
+        download(
+            StatusCode::PARTIAL_CONTENT,
+            &[("content-range", "bytes 5-10/11")],
+            vec![" world"],
+        )
+    }
+
+    #[test]
+    fn test_extended_filename() {
+        assert_eq!(
+            parse_filename("attachment; filename=\"label.pdf\"; filename*=UTF-8''label%20%231.pdf").as_deref(),
+            Some("label #1.pdf")
+        );
+        assert_eq!(parse_filename("inline"), None);
+    }
+
+    #[tokio::test]
+    async fn test_write_to_reports_progress() {
+        let download = download(
+            StatusCode::OK,
+            &[("content-length", "11")],
+            vec!["hello", " ", "world"],
+        );
+
+        let mut output = Vec::new();
+        let mut updates = Vec::new();
+        let written = download
+            .write_to(&mut output, |p| updates.push(p))
+            .await
+            .unwrap();
+
+        assert_eq!(written, 11);
+        assert_eq!(output, b"hello world");
+        assert_eq!(updates.len(), 3);
+        assert_eq!(
+            updates.last(),
+            Some(&DownloadProgress {
+                downloaded: 11,
+                total: Some(11)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bytes() {
+        let body = download_partial().bytes().await.unwrap();
+        assert_eq!(body, Bytes::from(" world"));
+    }
+}
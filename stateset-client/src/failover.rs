@@ -0,0 +1,326 @@
+//! Failover between the base URL and alternative endpoints
+//!
+//! Requests are built against [`Config::base_url`] and routed, attempt by
+//! attempt, to the first endpoint in rotation: the base URL, then each
+//! [`Config::failover_endpoints`] entry in order. An endpoint leaves the
+//! rotation when a connection to it fails, or after
+//! [`FailoverSettings::failure_threshold`](stateset_core::FailoverSettings)
+//! consecutive server errors or timeouts, and rejoins once its cool-down has
+//! passed. A single failure after rejoining takes it out again, so a
+//! recovering endpoint is probed with one request at a time rather than a
+//! burst. Retries therefore land on the next endpoint as soon as the current
+//! one is out of rotation.
+//!
+//! The scheme, host and port change, and the path is rebased from the base
+//! URL's path onto the endpoint's, so an endpoint like
+//! `https://gw.example.com/eu` receives `/eu/api/v1/...`. Requests to other
+//! origins, such as pre-signed download URLs, are sent unchanged. Health is shared by all
+//! clones of a client.
+
+use crate::response::HttpResponse;
+use stateset_core::config::PRIMARY_ENDPOINT;
+use stateset_core::{Config, Error, Result};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Health of one endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// Endpoint name; the base URL is named `"primary"`
+    pub name: String,
+    /// Base URL of the endpoint
+    pub url: Url,
+    /// Whether requests are currently routed to the endpoint
+    pub available: bool,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Time until the endpoint rejoins the rotation
+    pub retry_in: Option<Duration>,
+    /// Attempts sent to the endpoint
+    pub requests: u64,
+    /// Attempts that failed with a connection error, timeout or 5xx
+    pub failures: u64,
+}
+
+/// How an attempt against an endpoint went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    /// Any response below 500
+    Success,
+    /// A server error, timeout or broken connection
+    Failure,
+    /// The connection could not be established
+    Unreachable,
+}
+
+impl Outcome {
+    /// Classify the result of sending a request
+    pub(crate) fn of(result: &Result<HttpResponse>) -> Self {
+        match result {
+            Ok(response) if response.status().is_server_error() => Outcome::Failure,
+            Ok(_) => Outcome::Success,
+            Err(error) => Self::of_error(error),
+        }
+    }
+
+    /// Classify a transport error
+    pub(crate) fn of_error(error: &Error) -> Self {
+        if error.request_sent() {
+            Outcome::Failure
+        } else {
+            Outcome::Unreachable
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    down_until: Option<Instant>,
+    requests: u64,
+    failures: u64,
+}
+
+/// Endpoints in failover order and their health
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    endpoints: Vec<(String, Url)>,
+    failure_threshold: u32,
+    cool_down: Duration,
+    state: Mutex<Vec<State>>,
+}
+
+impl Endpoints {
+    pub(crate) fn from_config(config: &Config) -> Self {
+        let endpoints: Vec<_> = std::iter::once((PRIMARY_ENDPOINT.to_string(), config.base_url.clone()))
+            .chain(
+                config
+                    .failover_endpoints
+                    .iter()
+                    .map(|endpoint| (endpoint.name.clone(), endpoint.url.clone())),
+            )
+            .collect();
+        let state = endpoints.iter().map(|_| State::default()).collect();
+
+        Self {
+            endpoints,
+            failure_threshold: config.failover.failure_threshold.max(1),
+            cool_down: config.failover.cool_down,
+            state: Mutex::new(state),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<State>> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Index of the endpoint for the next attempt
+    ///
+    /// If every endpoint is out of rotation, the one that rejoins first.
+    pub(crate) fn select(&self) -> usize {
+        if self.endpoints.len() == 1 {
+            return 0;
+        }
+
+        let now = Instant::now();
+        let state = self.lock();
+        state
+            .iter()
+            .position(|endpoint| endpoint.down_until.is_none_or(|until| until <= now))
+            .or_else(|| {
+                state
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, endpoint)| endpoint.down_until)
+                    .map(|(index, _)| index)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Point a request built against the base URL at an endpoint
+    pub(crate) fn route(&self, request: &mut reqwest::Request, index: usize) {
+        let primary = &self.endpoints[0].1;
+        let target = &self.endpoints[index].1;
+        let url = request.url_mut();
+        if index == 0 || url.origin() != primary.origin() {
+            return;
+        }
+
+        // Both schemes are http or https, so these cannot fail
+        let _ = url.set_scheme(target.scheme());
+        let _ = url.set_host(target.host_str());
+        let _ = url.set_port(target.port());
+
+        let primary_base = primary.path().trim_end_matches('/');
+        let target_base = target.path().trim_end_matches('/');
+        if primary_base != target_base {
+            let path = url.path();
+            let relative = path
+                .strip_prefix(primary_base)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .unwrap_or(path);
+            let rebased = format!("{}{}", target_base, relative);
+            url.set_path(&rebased);
+        }
+    }
+
+    /// Record the outcome of an attempt against an endpoint
+    pub(crate) fn record(&self, index: usize, outcome: Outcome) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let endpoint = &mut state[index];
+        endpoint.requests += 1;
+
+        if outcome == Outcome::Success {
+            endpoint.consecutive_failures = 0;
+            endpoint.down_until = None;
+            return;
+        }
+
+        endpoint.failures += 1;
+        endpoint.consecutive_failures += 1;
+        if outcome == Outcome::Unreachable || endpoint.consecutive_failures >= self.failure_threshold {
+            if self.endpoints.len() > 1 && endpoint.down_until.is_none_or(|until| until <= now) {
+                log::warn!(
+                    "Endpoint {} ({}) is failing, taking it out of rotation for {:?}",
+                    self.endpoints[index].0,
+                    self.endpoints[index].1,
+                    self.cool_down
+                );
+            }
+            endpoint.down_until = Some(now + self.cool_down);
+            // One more failure after the cool-down takes it out again
+            endpoint.consecutive_failures = endpoint.consecutive_failures.max(self.failure_threshold - 1);
+        }
+    }
+
+    /// Name of the endpoint at `index`
+    pub(crate) fn name(&self, index: usize) -> &str {
+        &self.endpoints[index].0
+    }
+
+    /// Name of the endpoint a URL belongs to
+    pub(crate) fn name_of(&self, url: &Url) -> Option<&str> {
+        // Endpoints may share an origin; the longest matching base path wins
+        let origin = url.origin();
+        self.endpoints
+            .iter()
+            .filter(|(_, base)| base.origin() == origin)
+            .filter(|(_, base)| url.path().starts_with(base.path().trim_end_matches('/')))
+            .max_by_key(|(_, base)| base.path().trim_end_matches('/').len())
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Current health of every endpoint, in failover order
+    pub(crate) fn health(&self) -> Vec<EndpointHealth> {
+        let now = Instant::now();
+        let state = self.lock();
+        self.endpoints
+            .iter()
+            .zip(state.iter())
+            .map(|((name, url), endpoint)| {
+                let retry_in = endpoint
+                    .down_until
+                    .map(|until| until.saturating_duration_since(now))
+                    .filter(|remaining| !remaining.is_zero());
+                EndpointHealth {
+                    name: name.clone(),
+                    url: url.clone(),
+                    available: retry_in.is_none(),
+                    consecutive_failures: endpoint.consecutive_failures,
+                    retry_in,
+                    requests: endpoint.requests,
+                    failures: endpoint.failures,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stateset_core::FailoverSettings;
+
+    fn endpoints(cool_down: Duration) -> Endpoints {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .failover_endpoint("eu", "https://eu.api.stateset.io")
+            .failover(FailoverSettings {
+                failure_threshold: 2,
+                cool_down,
+            })
+            .build()
+            .unwrap();
+        Endpoints::from_config(&config)
+    }
+
+    #[test]
+    fn test_server_error_burst_fails_over_and_primary_returns() {
+        let endpoints = endpoints(Duration::from_millis(20));
+        endpoints.record(0, Outcome::Failure);
+        assert_eq!(endpoints.select(), 0);
+        endpoints.record(0, Outcome::Failure);
+        assert_eq!(endpoints.select(), 1);
+        assert!(!endpoints.health()[0].available);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(endpoints.select(), 0);
+
+        // A single failure while probing takes it out again
+        endpoints.record(0, Outcome::Failure);
+        assert_eq!(endpoints.select(), 1);
+    }
+
+    #[test]
+    fn test_unreachable_endpoint_leaves_rotation_at_once() {
+        let endpoints = endpoints(Duration::from_secs(60));
+        endpoints.record(0, Outcome::Unreachable);
+        assert_eq!(endpoints.select(), 1);
+
+        // With every endpoint down, the one that rejoins first is used
+        endpoints.record(1, Outcome::Unreachable);
+        assert_eq!(endpoints.select(), 0);
+    }
+
+    #[test]
+    fn test_route_rewrites_only_the_api_origin() {
+        let endpoints = endpoints(Duration::from_secs(60));
+        let client = reqwest::Client::new();
+
+        let mut request = client.get("https://api.stateset.io/api/v1/orders?limit=5").build().unwrap();
+        endpoints.route(&mut request, 1);
+        assert_eq!(request.url().as_str(), "https://eu.api.stateset.io/api/v1/orders?limit=5");
+        assert_eq!(endpoints.name_of(request.url()), Some("eu"));
+
+        let mut request = client.get("https://files.example.com/export.csv").build().unwrap();
+        endpoints.route(&mut request, 1);
+        assert_eq!(request.url().host_str(), Some("files.example.com"));
+    }
+
+    #[test]
+    fn test_route_rebases_onto_endpoint_path() {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .failover_endpoint("eu", "https://gw.example.com/eu")
+            .failover_endpoint("us", "https://gw.example.com/us/")
+            .build()
+            .unwrap();
+        let endpoints = Endpoints::from_config(&config);
+        let client = reqwest::Client::new();
+
+        let mut request = client.get("https://api.stateset.io/api/v1/orders?limit=5").build().unwrap();
+        endpoints.route(&mut request, 1);
+        assert_eq!(request.url().as_str(), "https://gw.example.com/eu/api/v1/orders?limit=5");
+        assert_eq!(endpoints.name_of(request.url()), Some("eu"));
+
+        let mut request = client.get("https://api.stateset.io/api/v1/orders").build().unwrap();
+        endpoints.route(&mut request, 2);
+        assert_eq!(request.url().as_str(), "https://gw.example.com/us/api/v1/orders");
+        assert_eq!(endpoints.name_of(request.url()), Some("us"));
+    }
+}
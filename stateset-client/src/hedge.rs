@@ -0,0 +1,181 @@
+//! Request hedging for latency-sensitive reads
+//!
+//! A hedged request sends a duplicate of a safe (`GET`, `HEAD`, `OPTIONS`)
+//! request when the first one is slower than usual, keeps whichever answers
+//! first and cancels the rest. Hedging is opt-in through
+//! [`RequestOptions::hedge`](crate::request::RequestOptions::hedge) or
+//! [`Client::with_hedging`](crate::Client::with_hedging), and runs inside
+//! every retry attempt: a group of hedged requests that all fail counts as
+//! one failed attempt of the [`RetryPolicy`](crate::retry::RetryPolicy).
+//!
+//! Duplicates are paid for out of a load budget. Every hedgeable call earns
+//! `max_extra_load` hedges, so with the default of `0.1` at most about one
+//! request in ten is duplicated, even while the API is slow for everyone.
+
+use crate::retry::RetryBudget;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Latency samples kept for percentile delays
+const LATENCY_WINDOW: usize = 256;
+
+/// Samples required before a percentile delay is trusted
+const MIN_SAMPLES: usize = 20;
+
+/// When to send a hedge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgeDelay {
+    /// After a fixed delay
+    Fixed(Duration),
+    /// After the given percentile (0–100) of recently observed latencies,
+    /// or `fallback` until enough latencies have been observed
+    Percentile { percentile: f64, fallback: Duration },
+}
+
+/// Opt-in hedging for safe methods
+///
+/// Clones share latency history and the load budget, so attach one policy
+/// to every client that talks to the same endpoints.
+#[derive(Debug, Clone)]
+pub struct HedgePolicy {
+    delay: HedgeDelay,
+    max_hedges: u32,
+    min_delay: Duration,
+    budget: RetryBudget,
+    latencies: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl HedgePolicy {
+    /// Hedge after a fixed delay
+    pub fn fixed(delay: Duration) -> Self {
+        Self::new(HedgeDelay::Fixed(delay))
+    }
+
+    /// Hedge after the given percentile of observed latencies
+    ///
+    /// Until enough responses have been seen, hedges are sent after
+    /// `fallback`.
+    pub fn percentile(percentile: f64, fallback: Duration) -> Self {
+        Self::new(HedgeDelay::Percentile {
+            percentile: percentile.clamp(0.0, 100.0),
+            fallback,
+        })
+    }
+
+    fn new(delay: HedgeDelay) -> Self {
+        Self {
+            delay,
+            max_hedges: 1,
+            min_delay: Duration::from_millis(10),
+            budget: RetryBudget::new(0.1, 10),
+            latencies: Arc::new(Mutex::new(VecDeque::with_capacity(LATENCY_WINDOW))),
+        }
+    }
+
+    /// Send at most this many duplicates per request (default 1)
+    ///
+    /// Each further duplicate is sent after another hedge delay.
+    pub fn max_hedges(mut self, max_hedges: u32) -> Self {
+        self.max_hedges = max_hedges;
+        self
+    }
+
+    /// Never hedge sooner than this (default 10ms)
+    pub fn min_delay(mut self, min_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self
+    }
+
+    /// Limit duplicates to `ratio` per hedgeable request, with at most
+    /// `burst` duplicates banked (default 0.1 and 10)
+    pub fn max_extra_load(mut self, ratio: f64, burst: u32) -> Self {
+        self.budget = RetryBudget::new(ratio, burst);
+        self
+    }
+
+    /// Configured hedge delay
+    pub fn delay(&self) -> HedgeDelay {
+        self.delay
+    }
+
+    /// Delay before the next hedge is sent
+    pub fn current_delay(&self) -> Duration {
+        let delay = match self.delay {
+            HedgeDelay::Fixed(delay) => delay,
+            HedgeDelay::Percentile { percentile, fallback } => {
+                let mut samples: Vec<Duration> = self.lock().iter().copied().collect();
+                if samples.len() < MIN_SAMPLES {
+                    fallback
+                } else {
+                    samples.sort_unstable();
+                    let rank = (percentile / 100.0 * (samples.len() - 1) as f64).round() as usize;
+                    samples[rank.min(samples.len() - 1)]
+                }
+            }
+        };
+        delay.max(self.min_delay)
+    }
+
+    /// Maximum duplicates per request
+    pub(crate) fn hedges(&self) -> u32 {
+        self.max_hedges
+    }
+
+    /// Earn load budget for a hedgeable request
+    pub(crate) fn admit(&self) {
+        self.budget.deposit();
+    }
+
+    /// Spend load budget on a duplicate
+    pub(crate) fn try_hedge(&self) -> bool {
+        self.budget.try_withdraw()
+    }
+
+    /// Record the latency of a successful request
+    pub(crate) fn observe(&self, latency: Duration) {
+        let mut latencies = self.lock();
+        if latencies.len() == LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Duration>> {
+        match self.latencies.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_delay() {
+        let policy = HedgePolicy::percentile(90.0, Duration::from_millis(250));
+        assert_eq!(policy.current_delay(), Duration::from_millis(250));
+
+        for ms in 1..=100 {
+            policy.clone().observe(Duration::from_millis(ms));
+        }
+        assert_eq!(policy.current_delay(), Duration::from_millis(90));
+
+        let floor = policy.min_delay(Duration::from_millis(200));
+        assert_eq!(floor.current_delay(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_extra_load_budget() {
+        let policy = HedgePolicy::fixed(Duration::from_millis(50)).max_extra_load(0.5, 1);
+
+        assert!(policy.try_hedge());
+        assert!(!policy.try_hedge());
+
+        policy.admit();
+        policy.admit();
+        assert!(policy.try_hedge());
+    }
+}
@@ -0,0 +1,52 @@
+//! Idempotency key handling for mutating requests
+//!
+//! POST and PATCH requests are not safe to repeat: a retried order creation
+//! could create the order twice. StateSet deduplicates requests that carry the
+//! same `Idempotency-Key`, so the client attaches one key per logical
+//! operation and reuses it on every retry attempt.
+
+use reqwest::Method;
+
+/// Header carrying the idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Check whether an HTTP method is idempotent by definition
+pub fn is_idempotent_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE
+    )
+}
+
+/// Generate a new idempotency key
+pub fn generate_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Read the idempotency key from a request, if one is set
+pub(crate) fn key_of(request: &reqwest::Request) -> Option<String> {
+    request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotent_methods() {
+        assert!(is_idempotent_method(&Method::GET));
+        assert!(is_idempotent_method(&Method::PUT));
+        assert!(is_idempotent_method(&Method::DELETE));
+        assert!(!is_idempotent_method(&Method::POST));
+        assert!(!is_idempotent_method(&Method::PATCH));
+    }
+
+    #[test]
+    fn test_generated_keys_are_unique() {
+        assert_ne!(generate_key(), generate_key());
+    }
+}
@@ -0,0 +1,369 @@
+//! In-process metrics of client calls
+//!
+//! With a [`Metrics`] registry attached through
+//! [`Client::with_metrics`](crate::Client::with_metrics), every call is
+//! counted once, however many attempts it took: by final status, by
+//! [`Error::kind`] of the error that ended it, and in a latency histogram
+//! covering all attempts and retry delays. Retries are counted separately.
+//! Series are labelled by method, resource (`orders`, `inventory`, ...) and
+//! route template (see [`route_template`](crate::telemetry::route_template)),
+//! never by raw URL, so identifiers do not inflate cardinality.
+//!
+//! The client's [circuit breakers](crate::circuit_breaker), and any
+//! [`CircuitBreakerMiddleware`](crate::middleware::CircuitBreakerMiddleware)
+//! given the registry, report their states as well. Read everything back with
+//! [`Metrics::snapshot`], or, with the `prometheus` feature, render it in
+//! the Prometheus text exposition format.
+
+use crate::circuit_breaker::CircuitState;
+use crate::telemetry::route_template;
+use reqwest::{Method, StatusCode};
+use stateset_core::Error;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets
+pub const LATENCY_BUCKETS: [Duration; 11] = [
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// Latency distribution with the bounds of [`LATENCY_BUCKETS`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// Observations per bucket, not cumulative; the last entry counts
+    /// observations above the largest bound
+    pub buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    /// Number of observations
+    pub count: u64,
+    /// Sum of all observations
+    pub sum: Duration,
+}
+
+impl Histogram {
+    fn observe(&mut self, latency: Duration) {
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| latency <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum += latency;
+    }
+
+    /// Mean of all observations
+    pub fn mean(&self) -> Option<Duration> {
+        u32::try_from(self.count)
+            .ok()
+            .filter(|count| *count > 0)
+            .map(|count| self.sum / count)
+    }
+}
+
+/// Metrics of one method and route
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteMetrics {
+    /// Calls made, successful or not
+    pub requests: u64,
+    /// Calls by the status of the final response
+    pub statuses: BTreeMap<u16, u64>,
+    /// Failed calls by [`Error::kind`] of the final attempt's error
+    pub errors: BTreeMap<&'static str, u64>,
+    /// Attempts repeated after a retryable failure
+    pub retries: u64,
+    /// Call latency, including retries
+    pub latency: Histogram,
+}
+
+/// Labels of a [`RouteMetrics`] series
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RouteKey {
+    /// Resource group, such as `orders`
+    pub resource: String,
+    /// Route template, such as `/api/v1/orders/{id}`
+    pub route: String,
+    /// HTTP method
+    pub method: String,
+}
+
+impl RouteKey {
+    fn new(method: &Method, path: &str) -> Self {
+        Self {
+            resource: crate::request::endpoint_group(path).to_string(),
+            route: route_template(path),
+            method: method.to_string(),
+        }
+    }
+}
+
+/// Point-in-time copy of a [`Metrics`] registry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Call metrics per method and route
+    pub routes: BTreeMap<RouteKey, RouteMetrics>,
+    /// Circuit breaker states by name
+    pub circuit_breakers: BTreeMap<String, CircuitState>,
+}
+
+impl MetricsSnapshot {
+    /// Metrics of one method and route template
+    pub fn route(&self, method: &str, route: &str) -> Option<&RouteMetrics> {
+        self.routes
+            .iter()
+            .find(|(key, _)| key.method.eq_ignore_ascii_case(method) && key.route == route)
+            .map(|(_, metrics)| metrics)
+    }
+
+    /// Calls made across all routes
+    pub fn requests(&self) -> u64 {
+        self.routes.values().map(|route| route.requests).sum()
+    }
+
+    /// Failed calls across all routes
+    pub fn errors(&self) -> u64 {
+        self.routes.values().flat_map(|route| route.errors.values()).sum()
+    }
+
+    /// Render the snapshot in the Prometheus text exposition format
+    #[cfg(feature = "prometheus")]
+    pub fn to_prometheus(&self) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let labels = |key: &RouteKey| {
+            format!(
+                "resource=\"{}\",route=\"{}\",method=\"{}\"",
+                escape(&key.resource),
+                escape(&key.route),
+                escape(&key.method)
+            )
+        };
+
+        out.push_str("# HELP stateset_requests_total Calls made, by final status\n");
+        out.push_str("# TYPE stateset_requests_total counter\n");
+        for (key, route) in &self.routes {
+            let answered: u64 = route.statuses.values().sum();
+            for (status, count) in &route.statuses {
+                let _ = writeln!(out, "stateset_requests_total{{{},status=\"{}\"}} {}", labels(key), status, count);
+            }
+            if route.requests > answered {
+                let _ = writeln!(
+                    out,
+                    "stateset_requests_total{{{},status=\"none\"}} {}",
+                    labels(key),
+                    route.requests - answered
+                );
+            }
+        }
+
+        out.push_str("# HELP stateset_errors_total Failed calls, by error kind\n");
+        out.push_str("# TYPE stateset_errors_total counter\n");
+        for (key, route) in &self.routes {
+            for (kind, count) in &route.errors {
+                let _ = writeln!(out, "stateset_errors_total{{{},error=\"{}\"}} {}", labels(key), kind, count);
+            }
+        }
+
+        out.push_str("# HELP stateset_retries_total Attempts repeated after a retryable failure\n");
+        out.push_str("# TYPE stateset_retries_total counter\n");
+        for (key, route) in &self.routes {
+            let _ = writeln!(out, "stateset_retries_total{{{}}} {}", labels(key), route.retries);
+        }
+
+        out.push_str("# HELP stateset_request_duration_seconds Call latency, including retries\n");
+        out.push_str("# TYPE stateset_request_duration_seconds histogram\n");
+        for (key, route) in &self.routes {
+            let labels = labels(key);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&route.latency.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "stateset_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels,
+                    bound.as_secs_f64(),
+                    cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "stateset_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, route.latency.count
+            );
+            let _ = writeln!(
+                out,
+                "stateset_request_duration_seconds_sum{{{}}} {}",
+                labels,
+                route.latency.sum.as_secs_f64()
+            );
+            let _ = writeln!(out, "stateset_request_duration_seconds_count{{{}}} {}", labels, route.latency.count);
+        }
+
+        out.push_str("# HELP stateset_circuit_breaker_state Circuit breaker state: 0 closed, 1 open, 2 half-open\n");
+        out.push_str("# TYPE stateset_circuit_breaker_state gauge\n");
+        for (name, state) in &self.circuit_breakers {
+            let _ = writeln!(out, "stateset_circuit_breaker_state{{circuit=\"{}\"}} {}", escape(name), *state as u8);
+        }
+
+        out
+    }
+}
+
+/// Escape a Prometheus label value
+#[cfg(feature = "prometheus")]
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Registry of call metrics
+///
+/// Clones share the registry, so one registry can collect the metrics of
+/// several clients.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    shared: Arc<Mutex<MetricsSnapshot>>,
+}
+
+impl Metrics {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MetricsSnapshot> {
+        match self.shared.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn with_route(&self, method: &Method, path: &str, update: impl FnOnce(&mut RouteMetrics)) {
+        let key = RouteKey::new(method, path);
+        update(self.lock().routes.entry(key).or_default());
+    }
+
+    /// Copy of everything recorded so far
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.lock().clone()
+    }
+
+    /// Forget everything recorded so far; circuit breaker states are kept
+    pub fn reset(&self) {
+        self.lock().routes.clear();
+    }
+
+    /// Render the registry in the Prometheus text exposition format
+    #[cfg(feature = "prometheus")]
+    pub fn to_prometheus(&self) -> String {
+        self.snapshot().to_prometheus()
+    }
+
+    /// Record a call answered with `status`
+    pub(crate) fn record_response(&self, method: &Method, path: &str, status: StatusCode, latency: Duration) {
+        self.with_route(method, path, |route| {
+            route.requests += 1;
+            *route.statuses.entry(status.as_u16()).or_default() += 1;
+            route.latency.observe(latency);
+        });
+    }
+
+    /// Record a call that failed with `error`
+    pub(crate) fn record_error(&self, method: &Method, path: &str, error: &Error, latency: Duration) {
+        let cause = error.root_cause();
+        // Client-side timeouts carry a nominal 408 but never got a response
+        let status = cause
+            .status_code()
+            .filter(|_| !matches!(cause, Error::Timeout { .. }));
+
+        self.with_route(method, path, |route| {
+            route.requests += 1;
+            if let Some(status) = status {
+                *route.statuses.entry(status).or_default() += 1;
+            }
+            *route.errors.entry(cause.kind()).or_default() += 1;
+            route.latency.observe(latency);
+        });
+    }
+
+    /// Record that a call is about to be retried
+    pub(crate) fn record_retry(&self, method: &Method, path: &str) {
+        self.with_route(method, path, |route| route.retries += 1);
+    }
+
+    /// Whether `other` shares this registry
+    pub(crate) fn same_registry(&self, other: &Metrics) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    /// Record the current state of a circuit breaker
+    pub(crate) fn record_circuit_state(&self, name: &str, state: CircuitState) {
+        self.lock().circuit_breakers.insert(name.to_string(), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calls_are_labelled_by_route() {
+        let metrics = Metrics::new();
+        let millis = Duration::from_millis;
+        metrics.record_response(&Method::GET, "/api/v1/orders/ord_1", StatusCode::OK, millis(3));
+        metrics.record_response(&Method::GET, "/api/v1/orders/ord_2", StatusCode::OK, millis(40));
+        metrics.record_retry(&Method::GET, "/api/v1/orders/ord_3");
+        let timeout = Error::RetryExhausted {
+            attempts: 1,
+            operation: "execute_request".to_string(),
+            last_error: Box::new(Error::timeout(millis(30), "request")),
+            idempotency_key: None,
+        };
+        metrics.record_error(&Method::GET, "/api/v1/orders/ord_3", &timeout, millis(62));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.routes.len(), 1);
+        let (key, route) = snapshot.routes.iter().next().unwrap();
+        assert_eq!(key.resource, "orders");
+        assert_eq!(key.route, "/api/v1/orders/{id}");
+        assert_eq!(route.requests, 3);
+        assert_eq!(route.statuses, BTreeMap::from([(200, 2)]));
+        assert_eq!(route.errors, BTreeMap::from([("timeout", 1)]));
+        assert_eq!(route.retries, 1);
+        assert_eq!(route.latency.buckets[0], 1);
+        assert_eq!(route.latency.buckets[3], 1);
+        assert_eq!(route.latency.buckets[4], 1);
+        assert_eq!(route.latency.mean(), Some(millis(35)));
+        assert_eq!(snapshot.route("get", "/api/v1/orders/{id}"), Some(route));
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn test_prometheus_text_format() {
+        let metrics = Metrics::new();
+        metrics.record_response(&Method::GET, "/api/v1/orders/ord_1", StatusCode::OK, Duration::from_millis(20));
+        metrics.record_error(&Method::POST, "/api/v1/orders", &Error::NotFound, Duration::from_millis(2));
+        metrics.record_circuit_state("inventory", CircuitState::Open);
+
+        let text = metrics.to_prometheus();
+        let labels = r#"resource="orders",route="/api/v1/orders/{id}",method="GET""#;
+        assert!(text.contains(&format!("stateset_requests_total{{{},status=\"200\"}} 1\n", labels)));
+        assert!(text.contains(&format!("stateset_request_duration_seconds_bucket{{{},le=\"0.01\"}} 0\n", labels)));
+        assert!(text.contains(&format!("stateset_request_duration_seconds_bucket{{{},le=\"0.025\"}} 1\n", labels)));
+        assert!(text.contains(&format!("stateset_request_duration_seconds_count{{{}}} 1\n", labels)));
+        assert!(text.contains(
+            "stateset_errors_total{resource=\"orders\",route=\"/api/v1/orders\",method=\"POST\",error=\"not_found\"} 1\n"
+        ));
+        assert!(text.contains("stateset_circuit_breaker_state{circuit=\"inventory\"} 1\n"));
+        assert_eq!(escape("a\"b\\c"), "a\\\"b\\\\c");
+    }
+}
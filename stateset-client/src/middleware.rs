@@ -1,20 +1,34 @@
 //! Middleware for HTTP requests and responses
-
-use std::time::{Duration, Instant};
-use reqwest::{Request, Response};
+//!
+//! Middleware registered on a [`Client`](crate::Client) runs around every
+//! attempt, including retries. Request middleware runs in registration order
+//! before the request is sent; response middleware runs in reverse order once
+//! the response body has been buffered. Returning an `Err` from either hook
+//! short-circuits the attempt with that error.
+
+pub use crate::circuit_breaker::CircuitState;
+use crate::metrics::Metrics;
+use crate::response::HttpResponse;
+use futures::future::{self, BoxFuture};
+use std::time::Duration;
+use reqwest::Request;
 use stateset_core::{Error, Result};
 use std::collections::HashSet;
 
 /// Trait for request middleware
 pub trait RequestMiddleware: Send + Sync {
     /// Process the request before sending
-    fn process_request(&self, request: &mut Request) -> Result<()>;
+    fn process_request<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>>;
 }
 
 /// Trait for response middleware
 pub trait ResponseMiddleware: Send + Sync {
     /// Process the response after receiving
-    fn process_response(&self, response: &Response, duration: Duration) -> Result<()>;
+    fn process_response<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<()>>;
 }
 
 /// Logging middleware for requests and responses
@@ -155,7 +169,7 @@ impl LoggingMiddleware {
 }
 
 impl RequestMiddleware for LoggingMiddleware {
-    fn process_request(&self, request: &mut Request) -> Result<()> {
+    fn process_request<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
         if self.log_requests {
             log::info!(
                 "HTTP Request: {} {}",
@@ -193,16 +207,21 @@ impl RequestMiddleware for LoggingMiddleware {
                 }
             }
         }
-        Ok(())
+        Box::pin(future::ready(Ok(())))
     }
 }
 
 impl ResponseMiddleware for LoggingMiddleware {
-    fn process_response(&self, response: &Response, duration: Duration) -> Result<()> {
+    fn process_response<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<()>> {
         if self.log_responses {
             log::info!(
-                "HTTP Response: {} {} in {:?}",
+                "HTTP Response: {} {} {} in {:?}",
                 response.status().as_u16(),
+                response.method(),
                 response.url(),
                 duration
             );
@@ -216,49 +235,61 @@ impl ResponseMiddleware for LoggingMiddleware {
             }
 
             // Log response body if enabled
-            // Note: This is a limitation of the current design - we can't easily access
-            // the response body here without consuming it. In a real implementation,
-            // we'd need to restructure the middleware to work with a copy of the body.
             if self.log_response_bodies {
-                log::debug!("Response body logging requires middleware restructuring");
+                let bytes = response.body();
+                if bytes.len() <= self.max_body_size {
+                    if let Ok(body_str) = std::str::from_utf8(bytes) {
+                        let redacted_body = self.redact_sensitive_json(body_str);
+                        log::debug!("Response body: {}", redacted_body);
+                    } else {
+                        log::debug!("Response body: [BINARY DATA, {} bytes]", bytes.len());
+                    }
+                } else {
+                    log::debug!("Response body: [TOO LARGE, {} bytes]", bytes.len());
+                }
             }
         }
-        Ok(())
+        Box::pin(future::ready(Ok(())))
     }
 }
 
-/// Metrics middleware for collecting request statistics
-#[derive(Debug, Default)]
+/// Metrics middleware recording every response in a [`Metrics`] registry
+///
+/// Middleware only sees responses, one per attempt, so transport errors go
+/// unrecorded and each retry counts as a call. Prefer
+/// [`Client::with_metrics`](crate::Client::with_metrics), which records
+/// calls, errors and retries, and do not use both with the same registry.
+#[derive(Debug, Clone, Default)]
 pub struct MetricsMiddleware {
-    // In a real implementation, this would contain metrics collectors
-    // For now, we'll just log metrics
+    metrics: Metrics,
 }
 
 impl MetricsMiddleware {
+    /// Create a middleware with its own registry
     pub fn new() -> Self {
         Self::default()
     }
-}
 
-impl ResponseMiddleware for MetricsMiddleware {
-    fn process_response(&self, response: &Response, duration: Duration) -> Result<()> {
-        let status = response.status().as_u16();
-        let method = response.url().as_str(); // In a real implementation, get method from request
-        
-        // In a real implementation, these would be sent to a metrics system
-        log::trace!(
-            "Request metrics: method={}, status={}, duration_ms={}",
-            method,
-            status,
-            duration.as_millis()
-        );
+    /// Create a middleware recording into `metrics`
+    pub fn with_metrics(metrics: Metrics) -> Self {
+        Self { metrics }
+    }
 
-        // Track error rates
-        if status >= 400 {
-            log::trace!("Request error: status={}", status);
-        }
+    /// Registry the middleware records into
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
 
-        Ok(())
+impl ResponseMiddleware for MetricsMiddleware {
+    fn process_response<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+        duration: Duration,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.metrics
+            .record_response(response.method(), response.url().path(), response.status(), duration);
+        Box::pin(future::ready(Ok(())))
     }
 }
 
@@ -277,48 +308,53 @@ impl UserAgentMiddleware {
 }
 
 impl RequestMiddleware for UserAgentMiddleware {
-    fn process_request(&self, request: &mut Request) -> Result<()> {
-        // Note: In a real implementation, we'd need mutable access to headers
-        // This is a simplified example
-        log::debug!("Would set User-Agent to: {}", self.user_agent);
-        Ok(())
+    fn process_request<'a>(&'a self, request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
+        let result = reqwest::header::HeaderValue::from_str(&self.user_agent)
+            .map(|value| {
+                request.headers_mut().insert(reqwest::header::USER_AGENT, value);
+            })
+            .map_err(|_| Error::InvalidRequest {
+                message: format!("Invalid User-Agent header value: {}", self.user_agent),
+                parameter: Some("user_agent".to_string()),
+            });
+        Box::pin(future::ready(result))
     }
 }
 
-/// Rate limiting middleware with token bucket implementation
+/// Fixed-window rate limiting middleware that fails fast when exhausted
+///
+/// For limits that make callers wait instead of failing, configure
+/// `ConfigBuilder::rate_limit` and enable the `rate-limit` feature.
 #[derive(Debug)]
 pub struct RateLimitMiddleware {
     pub requests_per_minute: u32,
-    // In a real implementation, we'd use a proper token bucket or rate limiter
-    last_reset: std::time::Instant,
-    tokens_remaining: std::sync::atomic::AtomicU32,
+    window: std::sync::Mutex<(std::time::Instant, u32)>,
 }
 
 impl RateLimitMiddleware {
     pub fn new(requests_per_minute: u32) -> Self {
         Self {
             requests_per_minute,
-            last_reset: std::time::Instant::now(),
-            tokens_remaining: std::sync::atomic::AtomicU32::new(requests_per_minute),
+            window: std::sync::Mutex::new((std::time::Instant::now(), requests_per_minute)),
         }
     }
 
-    /// Check if a request should be allowed
+    /// Check if a request should be allowed, consuming a token if so
     pub fn check_rate_limit(&self) -> bool {
-        // Simplified rate limiting - in production use a proper rate limiter library
-        let elapsed = self.last_reset.elapsed();
-        if elapsed >= Duration::from_secs(60) {
-            // Reset tokens
-            self.tokens_remaining.store(
-                self.requests_per_minute,
-                std::sync::atomic::Ordering::Relaxed
-            );
-            return true;
+        let mut window = match self.window.lock() {
+            Ok(window) => window,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let (window_start, tokens_remaining) = &mut *window;
+
+        // Start a fresh window once the current one has elapsed
+        if window_start.elapsed() >= Duration::from_secs(60) {
+            *window_start = std::time::Instant::now();
+            *tokens_remaining = self.requests_per_minute;
         }
 
-        let current = self.tokens_remaining.load(std::sync::atomic::Ordering::Relaxed);
-        if current > 0 {
-            self.tokens_remaining.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+        if *tokens_remaining > 0 {
+            *tokens_remaining -= 1;
             true
         } else {
             false
@@ -327,18 +363,23 @@ impl RateLimitMiddleware {
 }
 
 impl RequestMiddleware for RateLimitMiddleware {
-    fn process_request(&self, _request: &mut Request) -> Result<()> {
+    fn process_request<'a>(&'a self, _request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
         if !self.check_rate_limit() {
-            return Err(Error::RateLimit {
+            return Box::pin(future::ready(Err(Error::RateLimit {
                 retry_after: Some(Duration::from_secs(60)),
-            });
+            })));
         }
         log::trace!("Rate limit check passed: {} requests/minute", self.requests_per_minute);
-        Ok(())
+        Box::pin(future::ready(Ok(())))
     }
 }
 
 /// Circuit breaker middleware for handling service failures
+///
+/// One breaker for every request, counting only server error responses.
+/// [`ConfigBuilder::circuit_breaker`](stateset_core::ConfigBuilder::circuit_breaker)
+/// enables per-route breakers that also count timeouts and network errors
+/// and limit half-open probes; see [`circuit_breaker`](crate::circuit_breaker).
 #[derive(Debug)]
 pub struct CircuitBreakerMiddleware {
     pub failure_threshold: u32,
@@ -346,13 +387,7 @@ pub struct CircuitBreakerMiddleware {
     failure_count: std::sync::atomic::AtomicU32,
     last_failure: std::sync::Mutex<Option<std::time::Instant>>,
     state: std::sync::atomic::AtomicU8, // 0 = Closed, 1 = Open, 2 = HalfOpen
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum CircuitState {
-    Closed = 0,
-    Open = 1,
-    HalfOpen = 2,
+    metrics: Option<(Metrics, String)>,
 }
 
 impl From<u8> for CircuitState {
@@ -374,13 +409,36 @@ impl CircuitBreakerMiddleware {
             failure_count: std::sync::atomic::AtomicU32::new(0),
             last_failure: std::sync::Mutex::new(None),
             state: std::sync::atomic::AtomicU8::new(CircuitState::Closed as u8),
+            metrics: None,
         }
     }
 
+    /// Report the breaker's state to `metrics` under `name`
+    pub fn with_metrics(mut self, metrics: &Metrics, name: impl Into<String>) -> Self {
+        let name = name.into();
+        metrics.record_circuit_state(&name, self.get_state());
+        self.metrics = Some((metrics.clone(), name));
+        self
+    }
+
+    /// Current state of the breaker
+    pub fn state(&self) -> CircuitState {
+        self.get_state()
+    }
+
     fn get_state(&self) -> CircuitState {
         self.state.load(std::sync::atomic::Ordering::Relaxed).into()
     }
 
+    fn set_state(&self, state: CircuitState) {
+        let previous = self.state.swap(state as u8, std::sync::atomic::Ordering::Relaxed);
+        if previous != state as u8 {
+            if let Some((metrics, name)) = &self.metrics {
+                metrics.record_circuit_state(name, state);
+            }
+        }
+    }
+
     fn can_execute(&self) -> bool {
         match self.get_state() {
             CircuitState::Closed => true,
@@ -389,7 +447,7 @@ impl CircuitBreakerMiddleware {
                 if let Ok(last_failure) = self.last_failure.lock() {
                     if let Some(last) = *last_failure {
                         if last.elapsed() >= self.recovery_timeout {
-                            self.state.store(CircuitState::HalfOpen as u8, std::sync::atomic::Ordering::Relaxed);
+                            self.set_state(CircuitState::HalfOpen);
                             return true;
                         }
                     }
@@ -402,7 +460,7 @@ impl CircuitBreakerMiddleware {
 
     fn record_success(&self) {
         self.failure_count.store(0, std::sync::atomic::Ordering::Relaxed);
-        self.state.store(CircuitState::Closed as u8, std::sync::atomic::Ordering::Relaxed);
+        self.set_state(CircuitState::Closed);
     }
 
     fn record_failure(&self) {
@@ -413,25 +471,29 @@ impl CircuitBreakerMiddleware {
         }
 
         if failures >= self.failure_threshold {
-            self.state.store(CircuitState::Open as u8, std::sync::atomic::Ordering::Relaxed);
+            self.set_state(CircuitState::Open);
         }
     }
 }
 
 impl RequestMiddleware for CircuitBreakerMiddleware {
-    fn process_request(&self, _request: &mut Request) -> Result<()> {
+    fn process_request<'a>(&'a self, _request: &'a mut Request) -> BoxFuture<'a, Result<()>> {
         if !self.can_execute() {
-            return Err(Error::ServiceUnavailable {
+            return Box::pin(future::ready(Err(Error::ServiceUnavailable {
                 message: "Circuit breaker is open".to_string(),
                 retry_after: Some(self.recovery_timeout),
-            });
+            })));
         }
-        Ok(())
+        Box::pin(future::ready(Ok(())))
     }
 }
 
 impl ResponseMiddleware for CircuitBreakerMiddleware {
-    fn process_response(&self, response: &Response, _duration: Duration) -> Result<()> {
+    fn process_response<'a>(
+        &'a self,
+        response: &'a mut HttpResponse,
+        _duration: Duration,
+    ) -> BoxFuture<'a, Result<()>> {
         let status = response.status().as_u16();
         
         if status >= 500 {
@@ -441,7 +503,7 @@ impl ResponseMiddleware for CircuitBreakerMiddleware {
             self.record_success();
         }
         
-        Ok(())
+        Box::pin(future::ready(Ok(())))
     }
 }
 
@@ -530,6 +592,30 @@ mod tests {
         assert_eq!(cb.get_state(), CircuitState::Closed);
     }
 
+    #[test]
+    fn test_metrics_middleware_and_circuit_breaker_report_to_registry() {
+        let metrics = Metrics::new();
+        let cb = CircuitBreakerMiddleware::new(1, Duration::from_secs(30)).with_metrics(&metrics, "orders");
+        let middleware = MetricsMiddleware::with_metrics(metrics.clone());
+
+        let mut response = HttpResponse::new(
+            reqwest::Method::GET,
+            "https://api.stateset.io/api/v1/orders/ord_1".parse().unwrap(),
+            reqwest::StatusCode::BAD_GATEWAY,
+            reqwest::header::HeaderMap::new(),
+            bytes::Bytes::new(),
+        );
+        let duration = Duration::from_millis(12);
+        futures::executor::block_on(middleware.process_response(&mut response, duration)).unwrap();
+        futures::executor::block_on(cb.process_response(&mut response, duration)).unwrap();
+
+        let snapshot = metrics.snapshot();
+        let route = snapshot.route("GET", "/api/v1/orders/{id}").unwrap();
+        assert_eq!(route.statuses.get(&502), Some(&1));
+        assert_eq!(snapshot.circuit_breakers["orders"], CircuitState::Open);
+        assert_eq!(cb.state(), CircuitState::Open);
+    }
+
     #[test]
     fn test_rate_limiter() {
         let limiter = RateLimitMiddleware::new(2);
@@ -0,0 +1,123 @@
+//! Client-side rate limiting
+//!
+//! Built from [`Config::rate_limit`] and [`Config::rate_limit_groups`]. A
+//! request waits until both the global quota and the quota of its endpoint
+//! group have a permit, instead of failing with a 429. The limiter lives
+//! behind an `Arc`, so every clone of a [`Client`](crate::Client) draws from
+//! the same budget.
+
+use governor::{DefaultDirectRateLimiter, Quota};
+use stateset_core::Config;
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+/// Governor-backed limiter with a global quota and per-group quotas
+#[derive(Debug)]
+pub struct RateLimiter {
+    global: Option<DefaultDirectRateLimiter>,
+    groups: HashMap<String, DefaultDirectRateLimiter>,
+}
+
+impl RateLimiter {
+    /// Build a limiter from the configuration, if any limit is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.rate_limit.is_none() && config.rate_limit_groups.is_empty() {
+            return None;
+        }
+
+        let global = config
+            .rate_limit
+            .and_then(|(requests, per)| quota(requests, per))
+            .map(DefaultDirectRateLimiter::direct);
+        let groups = config
+            .rate_limit_groups
+            .iter()
+            .filter_map(|(group, (requests, per))| {
+                quota(*requests, *per).map(|q| (group.clone(), DefaultDirectRateLimiter::direct(q)))
+            })
+            .collect();
+
+        Some(Self { global, groups })
+    }
+
+    /// Wait until a request to the given endpoint group is allowed
+    pub async fn acquire(&self, group: &str) {
+        if let Some(limiter) = &self.global {
+            limiter.until_ready().await;
+        }
+        if let Some(limiter) = self.groups.get(group) {
+            limiter.until_ready().await;
+        }
+    }
+
+    /// Take a permit for the given group without waiting
+    ///
+    /// Returns `false` if any applicable quota is exhausted. Each quota that
+    /// is checked consumes a permit.
+    pub fn try_acquire(&self, group: &str) -> bool {
+        let global = self.global.as_ref().is_none_or(|l| l.check().is_ok());
+        global && self.groups.get(group).is_none_or(|l| l.check().is_ok())
+    }
+}
+
+/// Convert "N requests per duration" into a governor quota allowing bursts of N
+fn quota(requests: u32, per: Duration) -> Option<Quota> {
+    let burst = NonZeroU32::new(requests)?;
+    Quota::with_period(per / requests).map(|q| q.allow_burst(burst))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_limiter_without_limits() {
+        let config = Config::default();
+        assert!(RateLimiter::from_config(&config).is_none());
+    }
+
+    #[test]
+    fn test_group_quotas_are_independent() {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .rate_limit_group("analytics", 1, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let limiter = RateLimiter::from_config(&config).unwrap();
+
+        assert!(limiter.try_acquire("analytics"));
+        assert!(!limiter.try_acquire("analytics"));
+        assert!(limiter.try_acquire("orders"));
+    }
+
+    #[test]
+    fn test_global_quota_allows_burst() {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .rate_limit(3, Duration::from_secs(60))
+            .build()
+            .unwrap();
+        let limiter = RateLimiter::from_config(&config).unwrap();
+
+        assert!(limiter.try_acquire("orders"));
+        assert!(limiter.try_acquire("inventory"));
+        assert!(limiter.try_acquire("orders"));
+        assert!(!limiter.try_acquire("orders"));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_permit() {
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .rate_limit(1, Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let limiter = RateLimiter::from_config(&config).unwrap();
+
+        let start = std::time::Instant::now();
+        limiter.acquire("orders").await;
+        limiter.acquire("orders").await;
+        assert!(start.elapsed() >= Duration::from_millis(80));
+    }
+}
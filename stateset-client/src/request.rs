@@ -1,8 +1,36 @@
 //! Request building utilities
 
+use crate::response::ResponseCapture;
+use crate::concurrency::Priority;
+use crate::hedge::HedgePolicy;
+use crate::retry::RetryPolicy;
 use stateset_core::{Error, Result};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Resource group of an API path, used to key per-endpoint policies
+///
+/// Skips everything up to an `api` segment, such as a gateway prefix, and a
+/// version segment such as `v1`, so `/api/v1/analytics/reports/42` and
+/// `/stateset/api/v2/analytics` belong to the `analytics` group.
+pub fn endpoint_group(path: &str) -> &str {
+    let mut segments = path.split('/').filter(|s| !s.is_empty()).peekable();
+
+    if path.split('/').any(|s| s == "api") {
+        segments.by_ref().find(|s| *s == "api");
+    }
+    if let Some(segment) = segments.peek() {
+        let is_version = segment.len() > 1
+            && segment.starts_with('v')
+            && segment[1..].chars().all(|c| c.is_ascii_digit());
+        if is_version {
+            segments.next();
+        }
+    }
+
+    segments.next().unwrap_or("")
+}
 
 /// Options for list requests
 #[derive(Debug, Clone, Default, serde::Serialize)]
@@ -132,6 +160,148 @@ impl ListOptions {
     }
 }
 
+/// Per-call overrides of the client configuration
+///
+/// Apply to a whole client with [`Client::with_options`](crate::Client::with_options)
+/// or to one resource with e.g. `client.shipments().with_options(..)`. Unset
+/// fields fall back to the client's [`Config`](stateset_core::Config).
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    pub timeout: Option<Duration>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub headers: HashMap<String, String>,
+    pub idempotency_key: Option<String>,
+    pub deadline: Option<Instant>,
+    pub deadline_in: Option<Duration>,
+    pub capture: Option<ResponseCapture>,
+    pub compress_request: Option<bool>,
+    pub hedge: Option<HedgePolicy>,
+    pub priority: Option<Priority>,
+    pub api_version: Option<String>,
+}
+
+impl RequestOptions {
+    /// Create empty request options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the timeout of each attempt
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Override the retry policy
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Disable retries
+    pub fn no_retries(self) -> Self {
+        let policy = RetryPolicy {
+            max_attempts: 0,
+            ..RetryPolicy::default()
+        };
+        self.retry_policy(policy)
+    }
+
+    /// Add an extra header, replacing any default header of the same name
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Use a specific idempotency key
+    ///
+    /// The key is sent with every request made with these options, so scope
+    /// it to a single logical operation.
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Give up once this instant has passed, across all retry attempts
+    ///
+    /// The instant is fixed, so a client built with these options fails
+    /// every call made after it; use [`deadline_in`](Self::deadline_in) for
+    /// clients that are kept around.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Give up once `duration` has elapsed from the start of each call,
+    /// across all retry attempts
+    pub fn deadline_in(mut self, duration: Duration) -> Self {
+        self.deadline_in = Some(duration);
+        self
+    }
+
+    /// The earliest deadline of a call started at `start`
+    pub(crate) fn deadline_from(&self, start: Instant) -> Option<Instant> {
+        let relative = self.deadline_in.map(|duration| start + duration);
+        match (self.deadline, relative) {
+            (Some(deadline), Some(relative)) => Some(deadline.min(relative)),
+            (deadline, relative) => deadline.or(relative),
+        }
+    }
+
+    /// Record the metadata of each successful call into `capture`
+    pub fn capture(mut self, capture: ResponseCapture) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Force (`true`) or prevent (`false`) request body compression,
+    /// overriding the configured threshold and exempt groups
+    pub fn compress_request(mut self, compress: bool) -> Self {
+        self.compress_request = Some(compress);
+        self
+    }
+
+    /// Hedge safe requests with `policy`
+    pub fn hedge(mut self, policy: HedgePolicy) -> Self {
+        self.hedge = Some(policy);
+        self
+    }
+
+    /// Schedule requests in the given priority class
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Mark requests as bulk work that must not crowd out interactive traffic
+    pub fn background(self) -> Self {
+        self.priority(Priority::Background)
+    }
+
+    /// Build resource paths for an API version, e.g. `"v2"`, overriding the
+    /// configured and pinned versions
+    pub fn api_version(mut self, version: impl Into<String>) -> Self {
+        self.api_version = Some(version.into());
+        self
+    }
+
+    /// Layer `other` on top of these options; fields set in `other` win
+    pub fn merge(mut self, other: RequestOptions) -> Self {
+        self.timeout = other.timeout.or(self.timeout);
+        self.retry_policy = other.retry_policy.or(self.retry_policy);
+        self.headers.extend(other.headers);
+        self.idempotency_key = other.idempotency_key.or(self.idempotency_key);
+        self.deadline = other.deadline.or(self.deadline);
+        self.deadline_in = other.deadline_in.or(self.deadline_in);
+        self.capture = other.capture.or(self.capture);
+        self.compress_request = other.compress_request.or(self.compress_request);
+        self.hedge = other.hedge.or(self.hedge);
+        self.priority = other.priority.or(self.priority);
+        self.api_version = other.api_version.or(self.api_version);
+        self
+    }
+}
+
 /// Builder for list requests with filters
 #[derive(Debug, Clone)]
 pub struct ListRequestBuilder<F> {
@@ -184,7 +354,7 @@ where
     }
 
     /// Set sorting by field and order (alias for sort)
-    pub fn sort_by(mut self, field: impl Into<String>, order: SortOrder) -> Self {
+    pub fn sort_by(self, field: impl Into<String>, order: SortOrder) -> Self {
         self.sort(field, order)
     }
 
@@ -280,22 +450,17 @@ pub struct UpdateRequestBuilder<T> {
 }
 
 /// Strategy for merging updates
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum MergeStrategy {
     /// Replace all fields (default)
     Replace,
     /// Merge only non-null fields
+    #[default]
     Merge,
     /// Use PATCH semantics
     Patch,
 }
 
-impl Default for MergeStrategy {
-    fn default() -> Self {
-        MergeStrategy::Merge
-    }
-}
-
 impl<T> UpdateRequestBuilder<T> {
     /// Create a new update request builder
     pub fn new() -> Self {
@@ -337,6 +502,35 @@ impl<T> Default for UpdateRequestBuilder<T> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_endpoint_group() {
+        assert_eq!(endpoint_group("/api/v1/analytics/reports/42"), "analytics");
+        assert_eq!(endpoint_group("/api/v1/orders"), "orders");
+        assert_eq!(endpoint_group("/v2/warranty-claims/1"), "warranty-claims");
+        assert_eq!(endpoint_group("/inventory/items"), "inventory");
+        assert_eq!(endpoint_group("/gateway/stateset/api/v2/orders/1"), "orders");
+        assert_eq!(endpoint_group("/"), "");
+    }
+
+    #[test]
+    fn test_request_options_merge() {
+        let base = RequestOptions::new()
+            .timeout(Duration::from_secs(10))
+            .header("X-Team", "fulfillment")
+            .idempotency_key("key-1");
+        let merged = base.merge(
+            RequestOptions::new()
+                .timeout(Duration::from_secs(120))
+                .header("X-Priority", "high")
+                .no_retries(),
+        );
+
+        assert_eq!(merged.timeout, Some(Duration::from_secs(120)));
+        assert_eq!(merged.idempotency_key.as_deref(), Some("key-1"));
+        assert_eq!(merged.headers.len(), 2);
+        assert_eq!(merged.retry_policy.unwrap().max_attempts, 0);
+    }
+
     #[test]
     fn test_list_options_validation() {
         let options = ListOptions::new();
@@ -407,9 +601,10 @@ mod tests {
         let result = builder.build();
         assert!(result.is_ok());
 
-        let (options, _filters) = result.unwrap();
+        let (options, filters) = result.unwrap();
         assert_eq!(options.limit, Some(25));
         assert_eq!(options.page, Some(1));
+        assert!(filters.status.is_none());
     }
 
     #[test]
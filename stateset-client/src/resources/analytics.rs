@@ -1,7 +1,7 @@
 //! Analytics API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, download::Download, request::{ListRequestBuilder, RequestOptions}};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::analytics::{
     CreateAnalyticsReportRequest, AnalyticsReport, AnalyticsListFilters, ReportType, ChartType,
     AnalyticsQueryRequest, AnalyticsQueryResponse, SalesAnalytics, CustomerAnalytics,
@@ -19,37 +19,42 @@ impl AnalyticsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new analytics report
     pub async fn create_report(&self, request: CreateAnalyticsReportRequest) -> Result<AnalyticsReport> {
-        self.client.post("/api/v1/analytics/reports", &request).await
+        self.client.post(&self.client.api_path("/analytics/reports"), &request).await
     }
 
     /// Get an analytics report by ID
     pub async fn get_report(&self, id: impl Into<ResourceId>) -> Result<AnalyticsReport> {
-        let path = format!("/api/v1/analytics/reports/{}", id.into());
+        let path = self.client.api_path(&format!("/analytics/reports/{}", id.into()));
         self.client.get(&path).await
     }
 
     /// Update an analytics report
     pub async fn update_report(&self, id: impl Into<ResourceId>, request: CreateAnalyticsReportRequest) -> Result<AnalyticsReport> {
-        let path = format!("/api/v1/analytics/reports/{}", id.into());
+        let path = self.client.api_path(&format!("/analytics/reports/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete an analytics report
     pub async fn delete_report(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/analytics/reports/{}", id.into());
+        let path = self.client.api_path(&format!("/analytics/reports/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Execute an analytics query
     pub async fn query(&self, request: AnalyticsQueryRequest) -> Result<AnalyticsQueryResponse> {
-        self.client.post("/api/v1/analytics/query", &request).await
+        self.client.post(&self.client.api_path("/analytics/query"), &request).await
     }
 
     /// Get sales analytics
     pub async fn sales_analytics(&self, date_range: Option<(String, String)>) -> Result<SalesAnalytics> {
-        let mut path = "/api/v1/analytics/sales".to_string();
+        let mut path = self.client.api_path("/analytics/sales");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -58,7 +63,7 @@ impl AnalyticsClient {
 
     /// Get customer analytics
     pub async fn customer_analytics(&self, date_range: Option<(String, String)>) -> Result<CustomerAnalytics> {
-        let mut path = "/api/v1/analytics/customers".to_string();
+        let mut path = self.client.api_path("/analytics/customers");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -67,7 +72,7 @@ impl AnalyticsClient {
 
     /// Get product analytics
     pub async fn product_analytics(&self, date_range: Option<(String, String)>) -> Result<ProductAnalytics> {
-        let mut path = "/api/v1/analytics/products".to_string();
+        let mut path = self.client.api_path("/analytics/products");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -76,7 +81,7 @@ impl AnalyticsClient {
 
     /// Get inventory analytics
     pub async fn inventory_analytics(&self, date_range: Option<(String, String)>) -> Result<InventoryAnalytics> {
-        let mut path = "/api/v1/analytics/inventory".to_string();
+        let mut path = self.client.api_path("/analytics/inventory");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -85,7 +90,7 @@ impl AnalyticsClient {
 
     /// Get real-time dashboard
     pub async fn realtime_dashboard(&self) -> Result<RealtimeDashboard> {
-        self.client.get("/api/v1/analytics/dashboard/realtime").await
+        self.client.get(&self.client.api_path("/analytics/dashboard/realtime")).await
     }
 
     /// List analytics reports
@@ -95,22 +100,29 @@ impl AnalyticsClient {
 
     /// Export analytics data
     pub async fn export(&self, report_id: impl Into<ResourceId>, format: &str) -> Result<Vec<u8>> {
-        let path = format!("/api/v1/analytics/reports/{}/export?format={}", report_id.into(), format);
-        // Note: This would need special handling for binary data
-        let response = self.client.get::<serde_json::Value>(&path).await?;
-        // Convert to bytes - this is a simplified implementation
-        Ok(response.to_string().into_bytes())
+        let download = self.export_stream(report_id, format).await?;
+        Ok(download.bytes().await?.to_vec())
+    }
+
+    /// Stream a report export (CSV, XLSX, PDF, ...) without buffering it
+    pub async fn export_stream(&self, report_id: impl Into<ResourceId>, format: &str) -> Result<Download> {
+        let path = self.client.api_path(&format!(
+            "/analytics/reports/{}/export?format={}",
+            report_id.into(),
+            urlencoding::encode(format)
+        ));
+        self.client.download(&path).await
     }
 
     /// Get revenue trends
     pub async fn revenue_trends(&self, period: &str) -> Result<serde_json::Value> {
-        let path = format!("/api/v1/analytics/trends/revenue?period={}", period);
+        let path = self.client.api_path(&format!("/analytics/trends/revenue?period={}", period));
         self.client.get(&path).await
     }
 
     /// Get conversion funnel
     pub async fn conversion_funnel(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value> {
-        let mut path = "/api/v1/analytics/funnel/conversion".to_string();
+        let mut path = self.client.api_path("/analytics/funnel/conversion");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -119,7 +131,7 @@ impl AnalyticsClient {
 
     /// Get cohort analysis
     pub async fn cohort_analysis(&self, cohort_type: &str) -> Result<serde_json::Value> {
-        let path = format!("/api/v1/analytics/cohort?type={}", cohort_type);
+        let path = self.client.api_path(&format!("/analytics/cohort?type={}", cohort_type));
         self.client.get(&path).await
     }
 }
@@ -157,7 +169,7 @@ impl AnalyticsListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<AnalyticsReport>> {
         self.client
-            .get_with_query("/api/v1/analytics/reports", &self.builder.build())
+            .get_with_query(&self.client.api_path("/analytics/reports"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
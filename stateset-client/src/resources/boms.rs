@@ -1,7 +1,7 @@
 //! BOMs API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions}};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::bom::{
     CreateBomRequest, Bom, BomListFilters, BomStatus, BomType, UpdateBomRequest,
     BomCostAnalysis, BomExplosion,
@@ -18,44 +18,49 @@ impl BomsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new BOM
     pub async fn create(&self, request: CreateBomRequest) -> Result<Bom> {
-        self.client.post("/api/v1/boms", &request).await
+        self.client.post(&self.client.api_path("/boms"), &request).await
     }
 
     /// Get a BOM by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Bom> {
-        let path = format!("/api/v1/boms/{}", id.into());
+        let path = self.client.api_path(&format!("/boms/{}", id.into()));
         self.client.get(&path).await
     }
 
     /// Update a BOM
     pub async fn update(&self, id: impl Into<ResourceId>, request: UpdateBomRequest) -> Result<Bom> {
-        let path = format!("/api/v1/boms/{}", id.into());
+        let path = self.client.api_path(&format!("/boms/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete a BOM
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/boms/{}", id.into());
+        let path = self.client.api_path(&format!("/boms/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Approve a BOM
     pub async fn approve(&self, id: impl Into<ResourceId>) -> Result<Bom> {
-        let path = format!("/api/v1/boms/{}/approve", id.into());
+        let path = self.client.api_path(&format!("/boms/{}/approve", id.into()));
         self.client.post::<Bom, _>(&path, &serde_json::json!({})).await
     }
 
     /// Get BOM cost analysis
     pub async fn cost_analysis(&self, id: impl Into<ResourceId>) -> Result<BomCostAnalysis> {
-        let path = format!("/api/v1/boms/{}/cost-analysis", id.into());
+        let path = self.client.api_path(&format!("/boms/{}/cost-analysis", id.into()));
         self.client.get(&path).await
     }
 
     /// Get BOM explosion (where-used analysis)
     pub async fn explosion(&self, component_id: impl Into<ResourceId>) -> Result<BomExplosion> {
-        let path = format!("/api/v1/boms/explosion/{}", component_id.into());
+        let path = self.client.api_path(&format!("/boms/explosion/{}", component_id.into()));
         self.client.get(&path).await
     }
 
@@ -66,7 +71,7 @@ impl BomsClient {
 
     /// Get BOMs by product
     pub async fn by_product(&self, product_id: impl Into<ResourceId>) -> Result<Vec<Bom>> {
-        let path = format!("/api/v1/boms/by-product/{}", product_id.into());
+        let path = self.client.api_path(&format!("/boms/by-product/{}", product_id.into()));
         self.client.get(&path).await
     }
 }
@@ -104,7 +109,7 @@ impl BomListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<Bom>> {
         self.client
-            .get_with_query("/api/v1/boms", &self.builder.build())
+            .get_with_query(&self.client.api_path("/boms"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
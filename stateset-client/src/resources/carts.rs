@@ -1,7 +1,7 @@
 //! Carts API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions}};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::cart::{
     CreateCartRequest, Cart, CartListFilters, CartStatus, CartType, UpdateCartRequest,
     AddCartItemRequest, UpdateCartItemRequest, ApplyCouponRequest, CartAnalytics,
@@ -18,74 +18,79 @@ impl CartsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new cart
     pub async fn create(&self, request: CreateCartRequest) -> Result<Cart> {
-        self.client.post("/api/v1/carts", &request).await
+        self.client.post(&self.client.api_path("/carts"), &request).await
     }
 
     /// Get a cart by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}", id.into());
+        let path = self.client.api_path(&format!("/carts/{}", id.into()));
         self.client.get(&path).await
     }
 
     /// Get a cart by token
     pub async fn get_by_token(&self, token: &str) -> Result<Cart> {
-        let path = format!("/api/v1/carts/token/{}", token);
+        let path = self.client.api_path(&format!("/carts/token/{}", token));
         self.client.get(&path).await
     }
 
     /// Update a cart
     pub async fn update(&self, id: impl Into<ResourceId>, request: UpdateCartRequest) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}", id.into());
+        let path = self.client.api_path(&format!("/carts/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete a cart
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/carts/{}", id.into());
+        let path = self.client.api_path(&format!("/carts/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Add item to cart
     pub async fn add_item(&self, cart_id: impl Into<ResourceId>, request: AddCartItemRequest) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/items", cart_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/items", cart_id.into()));
         self.client.post(&path, &request).await
     }
 
     /// Update cart item
     pub async fn update_item(&self, cart_id: impl Into<ResourceId>, item_id: impl Into<ResourceId>, request: UpdateCartItemRequest) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/items/{}", cart_id.into(), item_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/items/{}", cart_id.into(), item_id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Remove item from cart
     pub async fn remove_item(&self, cart_id: impl Into<ResourceId>, item_id: impl Into<ResourceId>) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/items/{}", cart_id.into(), item_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/items/{}", cart_id.into(), item_id.into()));
         self.client.delete(&path).await
     }
 
     /// Clear cart
     pub async fn clear(&self, cart_id: impl Into<ResourceId>) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/clear", cart_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/clear", cart_id.into()));
         self.client.post::<Cart, _>(&path, &serde_json::json!({})).await
     }
 
     /// Apply coupon to cart
     pub async fn apply_coupon(&self, cart_id: impl Into<ResourceId>, request: ApplyCouponRequest) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/coupons", cart_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/coupons", cart_id.into()));
         self.client.post(&path, &request).await
     }
 
     /// Remove coupon from cart
     pub async fn remove_coupon(&self, cart_id: impl Into<ResourceId>, coupon_code: &str) -> Result<Cart> {
-        let path = format!("/api/v1/carts/{}/coupons/{}", cart_id.into(), coupon_code);
+        let path = self.client.api_path(&format!("/carts/{}/coupons/{}", cart_id.into(), coupon_code));
         self.client.delete(&path).await
     }
 
     /// Convert cart to order
     pub async fn convert_to_order(&self, cart_id: impl Into<ResourceId>) -> Result<serde_json::Value> {
-        let path = format!("/api/v1/carts/{}/convert", cart_id.into());
+        let path = self.client.api_path(&format!("/carts/{}/convert", cart_id.into()));
         self.client.post::<serde_json::Value, _>(&path, &serde_json::json!({})).await
     }
 
@@ -96,7 +101,7 @@ impl CartsClient {
 
     /// Get cart analytics
     pub async fn analytics(&self, date_range: Option<(String, String)>) -> Result<CartAnalytics> {
-        let mut path = "/api/v1/carts/analytics".to_string();
+        let mut path = self.client.api_path("/carts/analytics");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -105,7 +110,7 @@ impl CartsClient {
 
     /// Get abandoned carts
     pub async fn abandoned(&self) -> Result<Vec<Cart>> {
-        self.client.get("/api/v1/carts/abandoned").await
+        self.client.get(&self.client.api_path("/carts/abandoned")).await
     }
 }
 
@@ -142,7 +147,7 @@ impl CartListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<Cart>> {
         self.client
-            .get_with_query("/api/v1/carts", &self.builder.build())
+            .get_with_query(&self.client.api_path("/carts"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
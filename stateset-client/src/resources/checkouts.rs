@@ -1,7 +1,7 @@
 //! Checkouts API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions}};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::checkout::{
     CreateCheckoutRequest, Checkout, CheckoutListFilters, CheckoutStatus, CheckoutStep,
     UpdateCheckoutRequest, CompleteCheckoutRequest, CheckoutCompletionResult,
@@ -19,62 +19,78 @@ impl CheckoutsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new checkout
     pub async fn create(&self, request: CreateCheckoutRequest) -> Result<Checkout> {
-        self.client.post("/api/v1/checkouts", &request).await
+        self.client.post(&self.client.api_path("/checkouts"), &request).await
     }
 
     /// Get a checkout by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}", id.into()));
         self.client.get(&path).await
     }
 
     /// Get a checkout by token
     pub async fn get_by_token(&self, token: &str) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/token/{}", token);
+        let path = self.client.api_path(&format!("/checkouts/token/{}", token));
         self.client.get(&path).await
     }
 
     /// Update a checkout
     pub async fn update(&self, id: impl Into<ResourceId>, request: UpdateCheckoutRequest) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Complete a checkout
     pub async fn complete(&self, id: impl Into<ResourceId>, request: CompleteCheckoutRequest) -> Result<CheckoutCompletionResult> {
-        let path = format!("/api/v1/checkouts/{}/complete", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/complete", id.into()));
         self.client.post(&path, &request).await
     }
 
+    /// Complete a checkout with a caller-supplied idempotency key
+    pub async fn complete_with_idempotency_key(
+        &self,
+        id: impl Into<ResourceId>,
+        request: CompleteCheckoutRequest,
+        key: impl AsRef<str>,
+    ) -> Result<CheckoutCompletionResult> {
+        let path = self.client.api_path(&format!("/checkouts/{}/complete", id.into()));
+        self.client.post_with_idempotency_key(&path, &request, key).await
+    }
+
     /// Cancel a checkout
     pub async fn cancel(&self, id: impl Into<ResourceId>) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}/cancel", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/cancel", id.into()));
         self.client.post::<Checkout, _>(&path, &serde_json::json!({})).await
     }
 
     /// Apply coupon to checkout
     pub async fn apply_coupon(&self, id: impl Into<ResourceId>, request: ApplyCheckoutCouponRequest) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}/coupons", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/coupons", id.into()));
         self.client.post(&path, &request).await
     }
 
     /// Apply gift card to checkout
     pub async fn apply_gift_card(&self, id: impl Into<ResourceId>, request: ApplyGiftCardRequest) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}/gift-cards", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/gift-cards", id.into()));
         self.client.post(&path, &request).await
     }
 
     /// Get shipping rates for checkout
     pub async fn get_shipping_rates(&self, id: impl Into<ResourceId>) -> Result<serde_json::Value> {
-        let path = format!("/api/v1/checkouts/{}/shipping-rates", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/shipping-rates", id.into()));
         self.client.get(&path).await
     }
 
     /// Update checkout step
     pub async fn update_step(&self, id: impl Into<ResourceId>, step: CheckoutStep) -> Result<Checkout> {
-        let path = format!("/api/v1/checkouts/{}/step", id.into());
+        let path = self.client.api_path(&format!("/checkouts/{}/step", id.into()));
         let body = serde_json::json!({"step": step});
         self.client.post(&path, &body).await
     }
@@ -86,7 +102,7 @@ impl CheckoutsClient {
 
     /// Get checkout abandonment analysis
     pub async fn abandonment_analysis(&self, date_range: Option<(String, String)>) -> Result<CheckoutAbandonmentAnalysis> {
-        let mut path = "/api/v1/checkouts/abandonment-analysis".to_string();
+        let mut path = self.client.api_path("/checkouts/abandonment-analysis");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -95,7 +111,7 @@ impl CheckoutsClient {
 
     /// Get abandoned checkouts
     pub async fn abandoned(&self) -> Result<Vec<Checkout>> {
-        self.client.get("/api/v1/checkouts/abandoned").await
+        self.client.get(&self.client.api_path("/checkouts/abandoned")).await
     }
 }
 
@@ -132,7 +148,7 @@ impl CheckoutListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<Checkout>> {
         self.client
-            .get_with_query("/api/v1/checkouts", &self.builder.build())
+            .get_with_query(&self.client.api_path("/checkouts"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
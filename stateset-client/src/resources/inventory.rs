@@ -1,6 +1,6 @@
 //! Inventory API client implementation
 
-use crate::Client;
+use crate::{Client, request::RequestOptions};
 use stateset_core::{Result, types::ResourceId};
 use stateset_models::inventory::{
     CreateReservationRequest, InventoryLevel, InventoryReservation, InventoryUpdate,
@@ -19,9 +19,14 @@ impl InventoryClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Get inventory levels for a specific item
     pub async fn get_levels(&self, item_id: impl Into<ResourceId>) -> Result<Vec<InventoryLevel>> {
-        let path = format!("/api/v1/inventory/items/{}/levels", item_id.into());
+        let path = self.client.api_path(&format!("/inventory/items/{}/levels", item_id.into()));
         self.client.get(&path).await
     }
 
@@ -31,17 +36,17 @@ impl InventoryClient {
         item_id: impl Into<ResourceId>,
         location_id: impl Into<ResourceId>,
     ) -> Result<InventoryLevel> {
-        let path = format!(
-            "/api/v1/inventory/items/{}/locations/{}/level",
+        let path = self.client.api_path(&format!(
+            "/inventory/items/{}/locations/{}/level",
             item_id.into(),
             location_id.into()
-        );
+        ));
         self.client.get(&path).await
     }
 
     /// Update inventory levels (adjustments)
     pub async fn adjust(&self, updates: Vec<InventoryUpdate>) -> Result<Vec<InventoryLevel>> {
-        self.client.post("/api/v1/inventory/adjust", &updates).await
+        self.client.post(&self.client.api_path("/inventory/adjust"), &updates).await
     }
 
     /// Update inventory in batch
@@ -58,7 +63,18 @@ impl InventoryClient {
         request: CreateReservationRequest,
     ) -> Result<InventoryReservation> {
         self.client
-            .post("/api/v1/inventory/reservations", &request)
+            .post(&self.client.api_path("/inventory/reservations"), &request)
+            .await
+    }
+
+    /// Create an inventory reservation with a caller-supplied idempotency key
+    pub async fn create_reservation_with_idempotency_key(
+        &self,
+        request: CreateReservationRequest,
+        key: impl AsRef<str>,
+    ) -> Result<InventoryReservation> {
+        self.client
+            .post_with_idempotency_key(&self.client.api_path("/inventory/reservations"), &request, key)
             .await
     }
 
@@ -67,7 +83,7 @@ impl InventoryClient {
         &self,
         id: impl Into<ResourceId>,
     ) -> Result<InventoryReservation> {
-        let path = format!("/api/v1/inventory/reservations/{}", id.into());
+        let path = self.client.api_path(&format!("/inventory/reservations/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -76,7 +92,7 @@ impl InventoryClient {
         &self,
         id: impl Into<ResourceId>,
     ) -> Result<InventoryReservation> {
-        let path = format!("/api/v1/inventory/reservations/{}/cancel", id.into());
+        let path = self.client.api_path(&format!("/inventory/reservations/{}/cancel", id.into()));
         self.client
             .post::<InventoryReservation, _>(&path, &serde_json::json!({}))
             .await
@@ -152,7 +168,7 @@ impl InventoryReservationBuilder {
     pub async fn execute(self) -> Result<InventoryReservation> {
         let request = self.builder.build();
         self.client
-            .post("/api/v1/inventory/reservations", &request)
+            .post(&self.client.api_path("/inventory/reservations"), &request)
             .await
     }
 } 
\ No newline at end of file
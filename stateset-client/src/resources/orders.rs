@@ -1,6 +1,6 @@
 //! Orders API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions, SortOrder}};
 use stateset_core::{Error, Result, ListResponse, types::{ResourceId, Timestamp}};
 use stateset_models::order::{
     CreateOrderRequest, Order, OrderListFilters, OrderStatus, UpdateOrderRequest,
@@ -19,14 +19,30 @@ impl OrdersClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new order
     pub async fn create(&self, request: CreateOrderRequest) -> Result<Order> {
-        self.client.post("/api/v1/orders", &request).await
+        self.client.post(&self.client.api_path("/orders"), &request).await
+    }
+
+    /// Create a new order with a caller-supplied idempotency key
+    pub async fn create_with_idempotency_key(
+        &self,
+        request: CreateOrderRequest,
+        key: impl AsRef<str>,
+    ) -> Result<Order> {
+        self.client
+            .post_with_idempotency_key(&self.client.api_path("/orders"), &request, key)
+            .await
     }
 
     /// Get an order by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Order> {
-        let path = format!("/api/v1/orders/{}", id.into());
+        let path = self.client.api_path(&format!("/orders/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -36,31 +52,31 @@ impl OrdersClient {
         id: impl Into<ResourceId>,
         request: UpdateOrderRequest,
     ) -> Result<Order> {
-        let path = format!("/api/v1/orders/{}", id.into());
+        let path = self.client.api_path(&format!("/orders/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete an order
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/orders/{}", id.into());
+        let path = self.client.api_path(&format!("/orders/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Cancel an order
     pub async fn cancel(&self, id: impl Into<ResourceId>) -> Result<Order> {
-        let path = format!("/api/v1/orders/{}/cancel", id.into());
+        let path = self.client.api_path(&format!("/orders/{}/cancel", id.into()));
         self.client.post::<Order, _>(&path, &serde_json::json!({})).await
     }
 
     /// Fulfill an order
     pub async fn fulfill(&self, id: impl Into<ResourceId>) -> Result<Order> {
-        let path = format!("/api/v1/orders/{}/fulfill", id.into());
+        let path = self.client.api_path(&format!("/orders/{}/fulfill", id.into()));
         self.client.post::<Order, _>(&path, &serde_json::json!({})).await
     }
 
     /// Refund an order
     pub async fn refund(&self, id: impl Into<ResourceId>, amount: Option<f64>) -> Result<Order> {
-        let path = format!("/api/v1/orders/{}/refund", id.into());
+        let path = self.client.api_path(&format!("/orders/{}/refund", id.into()));
         let body = if let Some(amount) = amount {
             serde_json::json!({"amount": amount})
         } else {
@@ -76,12 +92,12 @@ impl OrdersClient {
 
     /// Create multiple orders in batch
     pub async fn create_batch(&self, orders: Vec<CreateOrderRequest>) -> Result<Vec<Order>> {
-        self.client.post("/api/v1/orders/batch", &orders).await
+        self.client.post(&self.client.api_path("/orders/batch"), &orders).await
     }
 
     /// Get order analytics
     pub async fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value> {
-        let mut path = "/api/v1/orders/analytics".to_string();
+        let mut path = self.client.api_path("/orders/analytics");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -198,7 +214,7 @@ impl OrderListBuilder {
         }
 
         self.client
-            .get_with_query("/api/v1/orders", &query_params)
+            .get_with_query(&self.client.api_path("/orders"), &query_params)
             .await
     }
 
@@ -233,7 +249,7 @@ impl OrderListBuilder {
             query_params.insert("max_total".to_string(), max_total.to_string());
         }
 
-        Box::pin(self.client.stream_with_query("/api/v1/orders", &query_params))
+        Box::pin(self.client.stream_with_query(&self.client.api_path("/orders"), &query_params))
     }
 
     /// Collect all results into a vector (use with caution for large datasets)
@@ -264,7 +280,7 @@ impl OrderListBuilder {
         }
         
         let response: serde_json::Value = self.client
-            .get_with_query("/api/v1/orders", &query_params)
+            .get_with_query(&self.client.api_path("/orders"), &query_params)
             .await?;
             
         response.get("count")
@@ -340,7 +356,7 @@ impl OrderUpdateBuilder {
     /// Execute the update with validation
     pub async fn execute(self) -> Result<Order> {
         self.validate()?;
-        let path = format!("/api/v1/orders/{}", self.id);
+        let path = self.client.api_path(&format!("/orders/{}", self.id));
         self.client.patch(&path, &self.request).await
     }
 }
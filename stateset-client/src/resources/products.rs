@@ -1,10 +1,10 @@
 //! Products API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions}, upload::{MultipartUpload, UploadFile}};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::product::{
     CreateProductRequest, Product, ProductListFilters, ProductStatus, ProductType,
-    UpdateProductRequest,
+    UpdateProductRequest, ProductImage, ProductDocument,
 };
 
 /// Products API client
@@ -18,41 +18,70 @@ impl ProductsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new product
     pub async fn create(&self, request: CreateProductRequest) -> Result<Product> {
-        self.client.post("/api/v1/products", &request).await
+        self.client.post(&self.client.api_path("/products"), &request).await
     }
 
     /// Get a product by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Product> {
-        let path = format!("/api/v1/products/{}", id.into());
+        let path = self.client.api_path(&format!("/products/{}", id.into()));
         self.client.get(&path).await
     }
 
     /// Get a product by SKU
     pub async fn get_by_sku(&self, sku: &str) -> Result<Product> {
-        let path = format!("/api/v1/products/sku/{}", sku);
+        let path = self.client.api_path(&format!("/products/sku/{}", sku));
         self.client.get(&path).await
     }
 
     /// Update a product
     pub async fn update(&self, id: impl Into<ResourceId>, request: UpdateProductRequest) -> Result<Product> {
-        let path = format!("/api/v1/products/{}", id.into());
+        let path = self.client.api_path(&format!("/products/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete a product
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/products/{}", id.into());
+        let path = self.client.api_path(&format!("/products/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Duplicate a product
     pub async fn duplicate(&self, id: impl Into<ResourceId>) -> Result<Product> {
-        let path = format!("/api/v1/products/{}/duplicate", id.into());
+        let path = self.client.api_path(&format!("/products/{}/duplicate", id.into()));
         self.client.post::<Product, _>(&path, &serde_json::json!({})).await
     }
 
+    /// Upload a product image
+    pub async fn upload_image(
+        &self,
+        id: impl Into<ResourceId>,
+        file: UploadFile,
+        alt_text: Option<&str>,
+    ) -> Result<ProductImage> {
+        let path = self.client.api_path(&format!("/products/{}/images", id.into()));
+        let upload = MultipartUpload::new().file(file).text_opt("alt_text", alt_text);
+        self.client.upload(&path, upload).await
+    }
+
+    /// Upload a product document (manual, spec sheet, certificate, ...)
+    pub async fn upload_document(
+        &self,
+        id: impl Into<ResourceId>,
+        file: UploadFile,
+        title: Option<&str>,
+    ) -> Result<ProductDocument> {
+        let path = self.client.api_path(&format!("/products/{}/documents", id.into()));
+        let upload = MultipartUpload::new().file(file).text_opt("title", title);
+        self.client.upload(&path, upload).await
+    }
+
     /// List products
     pub fn list(&self) -> ProductListBuilder {
         ProductListBuilder::new(self.client.clone())
@@ -60,19 +89,19 @@ impl ProductsClient {
 
     /// Search products
     pub async fn search(&self, query: &str) -> Result<Vec<Product>> {
-        let path = format!("/api/v1/products/search?q={}", urlencoding::encode(query));
+        let path = self.client.api_path(&format!("/products/search?q={}", urlencoding::encode(query)));
         self.client.get(&path).await
     }
 
     /// Get products by category
     pub async fn by_category(&self, category_id: impl Into<ResourceId>) -> Result<Vec<Product>> {
-        let path = format!("/api/v1/products/by-category/{}", category_id.into());
+        let path = self.client.api_path(&format!("/products/by-category/{}", category_id.into()));
         self.client.get(&path).await
     }
 
     /// Get products by brand
     pub async fn by_brand(&self, brand_id: impl Into<ResourceId>) -> Result<Vec<Product>> {
-        let path = format!("/api/v1/products/by-brand/{}", brand_id.into());
+        let path = self.client.api_path(&format!("/products/by-brand/{}", brand_id.into()));
         self.client.get(&path).await
     }
 }
@@ -122,7 +151,7 @@ impl ProductListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<Product>> {
         self.client
-            .get_with_query("/api/v1/products", &self.builder.build())
+            .get_with_query(&self.client.api_path("/products"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
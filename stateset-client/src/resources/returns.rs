@@ -1,6 +1,6 @@
 //! Returns API client implementation
 
-use crate::Client;
+use crate::{Client, request::RequestOptions};
 use stateset_core::{Result, types::ResourceId};
 use stateset_models::returns::{CreateReturnRequest, Return, UpdateReturnRequest};
 
@@ -15,14 +15,19 @@ impl ReturnsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new return
     pub async fn create(&self, request: CreateReturnRequest) -> Result<Return> {
-        self.client.post("/api/v1/returns", &request).await
+        self.client.post(&self.client.api_path("/returns"), &request).await
     }
 
     /// Get a return by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}", id.into());
+        let path = self.client.api_path(&format!("/returns/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -32,13 +37,13 @@ impl ReturnsClient {
         id: impl Into<ResourceId>,
         request: UpdateReturnRequest,
     ) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}", id.into());
+        let path = self.client.api_path(&format!("/returns/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Approve a return
     pub async fn approve(&self, id: impl Into<ResourceId>) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}/approve", id.into());
+        let path = self.client.api_path(&format!("/returns/{}/approve", id.into()));
         self.client
             .post::<Return, _>(&path, &serde_json::json!({}))
             .await
@@ -46,7 +51,7 @@ impl ReturnsClient {
 
     /// Reject a return
     pub async fn reject(&self, id: impl Into<ResourceId>, reason: &str) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}/reject", id.into());
+        let path = self.client.api_path(&format!("/returns/{}/reject", id.into()));
         self.client
             .post::<Return, _>(&path, &serde_json::json!({ "reason": reason }))
             .await
@@ -54,7 +59,7 @@ impl ReturnsClient {
 
     /// Mark a return as received
     pub async fn receive(&self, id: impl Into<ResourceId>) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}/receive", id.into());
+        let path = self.client.api_path(&format!("/returns/{}/receive", id.into()));
         self.client
             .post::<Return, _>(&path, &serde_json::json!({}))
             .await
@@ -62,7 +67,7 @@ impl ReturnsClient {
 
     /// Process a return (issue refund/exchange)
     pub async fn process(&self, id: impl Into<ResourceId>) -> Result<Return> {
-        let path = format!("/api/v1/returns/{}/process", id.into());
+        let path = self.client.api_path(&format!("/returns/{}/process", id.into()));
         self.client
             .post::<Return, _>(&path, &serde_json::json!({}))
             .await
@@ -70,12 +75,12 @@ impl ReturnsClient {
 
     /// List returns
     pub async fn list(&self) -> Result<Vec<Return>> {
-        self.client.get("/api/v1/returns").await
+        self.client.get(&self.client.api_path("/returns")).await
     }
 
     /// List returns for a specific order
     pub async fn list_by_order(&self, order_id: impl Into<ResourceId>) -> Result<Vec<Return>> {
-        let path = format!("/api/v1/orders/{}/returns", order_id.into());
+        let path = self.client.api_path(&format!("/orders/{}/returns", order_id.into()));
         self.client.get(&path).await
     }
 
@@ -86,7 +91,7 @@ impl ReturnsClient {
     ) -> Result<Vec<Return>> {
         self.client
             .get_with_query(
-                "/api/v1/returns",
+                &self.client.api_path("/returns"),
                 &serde_json::json!({ "customer_id": customer_id.into() }),
             )
             .await
@@ -1,6 +1,6 @@
 //! Shipments API client implementation
 
-use crate::Client;
+use crate::{Client, download::Download, request::RequestOptions};
 use stateset_core::{Result, types::ResourceId};
 use stateset_models::shipment::{
     CreateShipmentRequest, Shipment, UpdateShipmentRequest,
@@ -17,14 +17,19 @@ impl ShipmentsClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new shipment
     pub async fn create(&self, request: CreateShipmentRequest) -> Result<Shipment> {
-        self.client.post("/api/v1/shipments", &request).await
+        self.client.post(&self.client.api_path("/shipments"), &request).await
     }
 
     /// Get a shipment by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Shipment> {
-        let path = format!("/api/v1/shipments/{}", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -32,7 +37,7 @@ impl ShipmentsClient {
     pub async fn get_by_tracking(&self, tracking_number: &str) -> Result<Shipment> {
         self.client
             .get_with_query(
-                "/api/v1/shipments/tracking",
+                &self.client.api_path("/shipments/tracking"),
                 &serde_json::json!({ "tracking_number": tracking_number }),
             )
             .await
@@ -44,13 +49,13 @@ impl ShipmentsClient {
         id: impl Into<ResourceId>,
         request: UpdateShipmentRequest,
     ) -> Result<Shipment> {
-        let path = format!("/api/v1/shipments/{}", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Cancel a shipment
     pub async fn cancel(&self, id: impl Into<ResourceId>) -> Result<Shipment> {
-        let path = format!("/api/v1/shipments/{}/cancel", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}/cancel", id.into()));
         self.client
             .post::<Shipment, _>(&path, &serde_json::json!({}))
             .await
@@ -58,7 +63,7 @@ impl ShipmentsClient {
 
     /// Mark a shipment as shipped
     pub async fn ship(&self, id: impl Into<ResourceId>) -> Result<Shipment> {
-        let path = format!("/api/v1/shipments/{}/ship", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}/ship", id.into()));
         self.client
             .post::<Shipment, _>(&path, &serde_json::json!({}))
             .await
@@ -66,7 +71,7 @@ impl ShipmentsClient {
 
     /// Mark a shipment as delivered
     pub async fn deliver(&self, id: impl Into<ResourceId>) -> Result<Shipment> {
-        let path = format!("/api/v1/shipments/{}/deliver", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}/deliver", id.into()));
         self.client
             .post::<Shipment, _>(&path, &serde_json::json!({}))
             .await
@@ -77,30 +82,40 @@ impl ShipmentsClient {
         &self,
         id: impl Into<ResourceId>,
     ) -> Result<Vec<TrackingEvent>> {
-        let path = format!("/api/v1/shipments/{}/tracking-events", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}/tracking-events", id.into()));
         self.client.get(&path).await
     }
 
     /// List shipments
     pub async fn list(&self) -> Result<Vec<Shipment>> {
-        self.client.get("/api/v1/shipments").await
+        self.client.get(&self.client.api_path("/shipments")).await
     }
 
     /// List shipments for a specific order
     pub async fn list_by_order(&self, order_id: impl Into<ResourceId>) -> Result<Vec<Shipment>> {
-        let path = format!("/api/v1/orders/{}/shipments", order_id.into());
+        let path = self.client.api_path(&format!("/orders/{}/shipments", order_id.into()));
         self.client.get(&path).await
     }
 
     /// Create shipping labels
     pub async fn create_label(&self, id: impl Into<ResourceId>) -> Result<ShippingLabel> {
-        let path = format!("/api/v1/shipments/{}/label", id.into());
+        let path = self.client.api_path(&format!("/shipments/{}/label", id.into()));
         self.client.post::<ShippingLabel, _>(&path, &serde_json::json!({})).await
     }
 
+    /// Download the label document (PDF, ZPL, PNG, ...) of a shipment
+    pub async fn download_label(&self, id: impl Into<ResourceId>, format: &str) -> Result<Download> {
+        let path = self.client.api_path(&format!(
+            "/shipments/{}/label/download?format={}",
+            id.into(),
+            urlencoding::encode(format)
+        ));
+        self.client.download(&path).await
+    }
+
     /// Get shipping rates
     pub async fn get_rates(&self, request: RateRequest) -> Result<Vec<ShippingRate>> {
-        self.client.post("/api/v1/shipments/rates", &request).await
+        self.client.post(&self.client.api_path("/shipments/rates"), &request).await
     }
 }
 
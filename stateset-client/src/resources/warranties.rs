@@ -1,14 +1,12 @@
 //! Warranties API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions}, upload::UploadFile};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::warranty::{
     CreateWarrantyRequest, Warranty, WarrantyListFilters, WarrantyStatus, WarrantyType,
     UpdateWarrantyRequest, CreateWarrantyClaimRequest, WarrantyClaim, WarrantyClaimListFilters,
-    UpdateWarrantyClaimRequest,
+    UpdateWarrantyClaimRequest, WarrantyClaimAttachment,
 };
-use futures::stream::{Stream, StreamExt};
-use std::pin::Pin;
 
 /// Warranties API client
 pub struct WarrantiesClient {
@@ -21,14 +19,19 @@ impl WarrantiesClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new warranty
     pub async fn create(&self, request: CreateWarrantyRequest) -> Result<Warranty> {
-        self.client.post("/api/v1/warranties", &request).await
+        self.client.post(&self.client.api_path("/warranties"), &request).await
     }
 
     /// Get a warranty by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<Warranty> {
-        let path = format!("/api/v1/warranties/{}", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -38,25 +41,25 @@ impl WarrantiesClient {
         id: impl Into<ResourceId>,
         request: UpdateWarrantyRequest,
     ) -> Result<Warranty> {
-        let path = format!("/api/v1/warranties/{}", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete a warranty
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/warranties/{}", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Activate a warranty
     pub async fn activate(&self, id: impl Into<ResourceId>) -> Result<Warranty> {
-        let path = format!("/api/v1/warranties/{}/activate", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}/activate", id.into()));
         self.client.post::<Warranty, _>(&path, &serde_json::json!({})).await
     }
 
     /// Void a warranty
     pub async fn void(&self, id: impl Into<ResourceId>, reason: Option<String>) -> Result<Warranty> {
-        let path = format!("/api/v1/warranties/{}/void", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}/void", id.into()));
         let body = if let Some(reason) = reason {
             serde_json::json!({"reason": reason})
         } else {
@@ -67,19 +70,19 @@ impl WarrantiesClient {
 
     /// Transfer a warranty to another customer
     pub async fn transfer(&self, id: impl Into<ResourceId>, new_customer_id: impl Into<ResourceId>) -> Result<Warranty> {
-        let path = format!("/api/v1/warranties/{}/transfer", id.into());
+        let path = self.client.api_path(&format!("/warranties/{}/transfer", id.into()));
         let body = serde_json::json!({"new_customer_id": new_customer_id.into()});
         self.client.post::<Warranty, _>(&path, &body).await
     }
 
     /// Create a warranty claim
     pub async fn create_claim(&self, request: CreateWarrantyClaimRequest) -> Result<WarrantyClaim> {
-        self.client.post("/api/v1/warranty-claims", &request).await
+        self.client.post(&self.client.api_path("/warranty-claims"), &request).await
     }
 
     /// Get a warranty claim by ID
     pub async fn get_claim(&self, id: impl Into<ResourceId>) -> Result<WarrantyClaim> {
-        let path = format!("/api/v1/warranty-claims/{}", id.into());
+        let path = self.client.api_path(&format!("/warranty-claims/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -89,13 +92,13 @@ impl WarrantiesClient {
         id: impl Into<ResourceId>,
         request: UpdateWarrantyClaimRequest,
     ) -> Result<WarrantyClaim> {
-        let path = format!("/api/v1/warranty-claims/{}", id.into());
+        let path = self.client.api_path(&format!("/warranty-claims/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Approve a warranty claim
     pub async fn approve_claim(&self, id: impl Into<ResourceId>, approved_amount: Option<serde_json::Value>) -> Result<WarrantyClaim> {
-        let path = format!("/api/v1/warranty-claims/{}/approve", id.into());
+        let path = self.client.api_path(&format!("/warranty-claims/{}/approve", id.into()));
         let body = if let Some(amount) = approved_amount {
             serde_json::json!({"approved_amount": amount})
         } else {
@@ -106,11 +109,21 @@ impl WarrantiesClient {
 
     /// Deny a warranty claim
     pub async fn deny_claim(&self, id: impl Into<ResourceId>, reason: String) -> Result<WarrantyClaim> {
-        let path = format!("/api/v1/warranty-claims/{}/deny", id.into());
+        let path = self.client.api_path(&format!("/warranty-claims/{}/deny", id.into()));
         let body = serde_json::json!({"denial_reason": reason});
         self.client.post::<WarrantyClaim, _>(&path, &body).await
     }
 
+    /// Upload an attachment (photo, receipt, ...) to a warranty claim
+    pub async fn upload_claim_attachment(
+        &self,
+        claim_id: impl Into<ResourceId>,
+        file: UploadFile,
+    ) -> Result<WarrantyClaimAttachment> {
+        let path = self.client.api_path(&format!("/warranty-claims/{}/attachments", claim_id.into()));
+        self.client.upload(&path, file).await
+    }
+
     /// List warranties with a builder pattern
     pub fn list(&self) -> WarrantyListBuilder {
         WarrantyListBuilder::new(self.client.clone())
@@ -123,19 +136,19 @@ impl WarrantiesClient {
 
     /// Get warranties by customer
     pub async fn by_customer(&self, customer_id: impl Into<ResourceId>) -> Result<Vec<Warranty>> {
-        let path = format!("/api/v1/warranties/by-customer/{}", customer_id.into());
+        let path = self.client.api_path(&format!("/warranties/by-customer/{}", customer_id.into()));
         self.client.get(&path).await
     }
 
     /// Get warranties by product
     pub async fn by_product(&self, product_id: impl Into<ResourceId>) -> Result<Vec<Warranty>> {
-        let path = format!("/api/v1/warranties/by-product/{}", product_id.into());
+        let path = self.client.api_path(&format!("/warranties/by-product/{}", product_id.into()));
         self.client.get(&path).await
     }
 
     /// Get warranty analytics
     pub async fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value> {
-        let mut path = "/api/v1/warranties/analytics".to_string();
+        let mut path = self.client.api_path("/warranties/analytics");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -188,7 +201,7 @@ impl WarrantyListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<Warranty>> {
         self.client
-            .get_with_query("/api/v1/warranties", &self.builder.build())
+            .get_with_query(&self.client.api_path("/warranties"), &self.builder.build())
             .await
     }
 }
@@ -220,7 +233,7 @@ impl WarrantyClaimListBuilder {
 
     pub async fn execute(self) -> Result<ListResponse<WarrantyClaim>> {
         self.client
-            .get_with_query("/api/v1/warranty-claims", &self.builder.build())
+            .get_with_query(&self.client.api_path("/warranty-claims"), &self.builder.build())
             .await
     }
 }
\ No newline at end of file
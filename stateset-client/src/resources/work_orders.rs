@@ -1,12 +1,12 @@
 //! Work Orders API client implementation
 
-use crate::{Client, request::{ListRequestBuilder, SortOrder}};
-use stateset_core::{Error, Result, ListResponse, types::ResourceId};
+use crate::{Client, request::{ListRequestBuilder, RequestOptions, SortOrder}, upload::UploadFile};
+use stateset_core::{Result, ListResponse, types::ResourceId};
 use stateset_models::work_order::{
     CreateWorkOrderRequest, WorkOrder, WorkOrderListFilters, WorkOrderStatus, WorkOrderPriority, 
-    WorkOrderType, UpdateWorkOrderRequest,
+    WorkOrderType, UpdateWorkOrderRequest, WorkOrderAttachment,
 };
-use futures::stream::{Stream, StreamExt};
+use futures::stream::Stream;
 use std::pin::Pin;
 
 /// Work Orders API client
@@ -20,14 +20,19 @@ impl WorkOrdersClient {
         Self { client }
     }
 
+    /// Apply per-call overrides to requests made through this client
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        Self::new(self.client.with_options(options))
+    }
+
     /// Create a new work order
     pub async fn create(&self, request: CreateWorkOrderRequest) -> Result<WorkOrder> {
-        self.client.post("/api/v1/work-orders", &request).await
+        self.client.post(&self.client.api_path("/work-orders"), &request).await
     }
 
     /// Get a work order by ID
     pub async fn get(&self, id: impl Into<ResourceId>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}", id.into()));
         self.client.get(&path).await
     }
 
@@ -37,25 +42,25 @@ impl WorkOrdersClient {
         id: impl Into<ResourceId>,
         request: UpdateWorkOrderRequest,
     ) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}", id.into()));
         self.client.patch(&path, &request).await
     }
 
     /// Delete a work order
     pub async fn delete(&self, id: impl Into<ResourceId>) -> Result<()> {
-        let path = format!("/api/v1/work-orders/{}", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}", id.into()));
         self.client.delete_no_content(&path).await
     }
 
     /// Start a work order
     pub async fn start(&self, id: impl Into<ResourceId>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/start", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/start", id.into()));
         self.client.post::<WorkOrder, _>(&path, &serde_json::json!({})).await
     }
 
     /// Complete a work order
     pub async fn complete(&self, id: impl Into<ResourceId>, completion_notes: Option<String>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/complete", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/complete", id.into()));
         let body = if let Some(notes) = completion_notes {
             serde_json::json!({"completion_notes": notes})
         } else {
@@ -66,13 +71,13 @@ impl WorkOrdersClient {
 
     /// Cancel a work order
     pub async fn cancel(&self, id: impl Into<ResourceId>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/cancel", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/cancel", id.into()));
         self.client.post::<WorkOrder, _>(&path, &serde_json::json!({})).await
     }
 
     /// Put a work order on hold
     pub async fn hold(&self, id: impl Into<ResourceId>, reason: Option<String>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/hold", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/hold", id.into()));
         let body = if let Some(reason) = reason {
             serde_json::json!({"reason": reason})
         } else {
@@ -83,17 +88,27 @@ impl WorkOrdersClient {
 
     /// Resume a work order from hold
     pub async fn resume(&self, id: impl Into<ResourceId>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/resume", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/resume", id.into()));
         self.client.post::<WorkOrder, _>(&path, &serde_json::json!({})).await
     }
 
     /// Assign a work order to a user
     pub async fn assign(&self, id: impl Into<ResourceId>, user_id: impl Into<ResourceId>) -> Result<WorkOrder> {
-        let path = format!("/api/v1/work-orders/{}/assign", id.into());
+        let path = self.client.api_path(&format!("/work-orders/{}/assign", id.into()));
         let body = serde_json::json!({"assigned_to": user_id.into()});
         self.client.post::<WorkOrder, _>(&path, &body).await
     }
 
+    /// Upload an attachment (manual, photo, sign-off sheet, ...) to a work order
+    pub async fn upload_attachment(
+        &self,
+        id: impl Into<ResourceId>,
+        file: UploadFile,
+    ) -> Result<WorkOrderAttachment> {
+        let path = self.client.api_path(&format!("/work-orders/{}/attachments", id.into()));
+        self.client.upload(&path, file).await
+    }
+
     /// List work orders with a builder pattern
     pub fn list(&self) -> WorkOrderListBuilder {
         WorkOrderListBuilder::new(self.client.clone())
@@ -101,12 +116,12 @@ impl WorkOrdersClient {
 
     /// Create multiple work orders in batch
     pub async fn create_batch(&self, work_orders: Vec<CreateWorkOrderRequest>) -> Result<Vec<WorkOrder>> {
-        self.client.post("/api/v1/work-orders/batch", &work_orders).await
+        self.client.post(&self.client.api_path("/work-orders/batch"), &work_orders).await
     }
 
     /// Get work order analytics
     pub async fn analytics(&self, date_range: Option<(String, String)>) -> Result<serde_json::Value> {
-        let mut path = "/api/v1/work-orders/analytics".to_string();
+        let mut path = self.client.api_path("/work-orders/analytics");
         if let Some((start, end)) = date_range {
             path.push_str(&format!("?start_date={}&end_date={}", start, end));
         }
@@ -115,19 +130,19 @@ impl WorkOrdersClient {
 
     /// Get work orders by asset
     pub async fn by_asset(&self, asset_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>> {
-        let path = format!("/api/v1/work-orders/by-asset/{}", asset_id.into());
+        let path = self.client.api_path(&format!("/work-orders/by-asset/{}", asset_id.into()));
         self.client.get(&path).await
     }
 
     /// Get work orders by customer
     pub async fn by_customer(&self, customer_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>> {
-        let path = format!("/api/v1/work-orders/by-customer/{}", customer_id.into());
+        let path = self.client.api_path(&format!("/work-orders/by-customer/{}", customer_id.into()));
         self.client.get(&path).await
     }
 
     /// Get work orders assigned to a user
     pub async fn by_assignee(&self, user_id: impl Into<ResourceId>) -> Result<Vec<WorkOrder>> {
-        let path = format!("/api/v1/work-orders/by-assignee/{}", user_id.into());
+        let path = self.client.api_path(&format!("/work-orders/by-assignee/{}", user_id.into()));
         self.client.get(&path).await
     }
 }
@@ -229,7 +244,7 @@ impl WorkOrderListBuilder {
     /// Execute the query and return a paginated response
     pub async fn execute(self) -> Result<ListResponse<WorkOrder>> {
         self.client
-            .get_with_query("/api/v1/work-orders", &self.builder.build())
+            .get_with_query(&self.client.api_path("/work-orders"), &self.builder.build())
             .await
     }
 
@@ -254,7 +269,7 @@ impl WorkOrderListBuilder {
             // Add other filters as needed...
             
             let response: ListResponse<WorkOrder> = self.client
-                .get_with_query("/api/v1/work-orders", &query_params)
+                .get_with_query(&self.client.api_path("/work-orders"), &query_params)
                 .await?;
 
             let page_size = response.data.len();
@@ -273,6 +288,6 @@ impl WorkOrderListBuilder {
     /// Return a stream of work orders
     pub fn stream(self) -> Pin<Box<dyn Stream<Item = Result<WorkOrder>> + Send>> {
         let query = self.builder.build();
-        Box::pin(self.client.stream_with_query("/api/v1/work-orders", &query))
+        Box::pin(self.client.stream_with_query(&self.client.api_path("/work-orders"), &query))
     }
 }
\ No newline at end of file
@@ -0,0 +1,319 @@
+//! Buffered HTTP responses and response metadata
+
+use crate::throttle::RateLimitStatus;
+use bytes::Bytes;
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use stateset_core::{Error, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// A fully buffered HTTP response
+///
+/// The client reads the whole body before handing the response to
+/// [`ResponseMiddleware`](crate::middleware::ResponseMiddleware), so middleware
+/// can inspect (or rewrite) the body without consuming it.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    method: Method,
+    url: Url,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl HttpResponse {
+    /// Create a new buffered response
+    pub fn new(
+        method: Method,
+        url: Url,
+        status: StatusCode,
+        headers: HeaderMap,
+        body: impl Into<Bytes>,
+    ) -> Self {
+        Self {
+            method,
+            url,
+            status,
+            headers,
+            body: body.into(),
+        }
+    }
+
+    /// Read a reqwest response into memory
+    pub(crate) async fn from_reqwest(method: Method, response: reqwest::Response) -> Result<Self> {
+        let url = response.url().clone();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Error::network(format!("Failed to read response body: {}", e)))?;
+
+        Ok(Self::new(method, url, status, headers, body))
+    }
+
+    /// HTTP method of the request that produced this response
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Final URL of the response
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// HTTP status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Mutable access to the response headers
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Get a header value as a string
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// Server-assigned request ID, if present
+    pub fn request_id(&self) -> Option<&str> {
+        self.header("x-request-id")
+    }
+
+    /// Raw response body
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Replace the response body
+    pub fn set_body(&mut self, body: impl Into<Bytes>) {
+        self.body = body.into();
+    }
+
+    /// Response body as UTF-8 text (lossy)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the response body as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| Error::network(format!("Failed to parse JSON response: {}", e)))
+    }
+}
+
+/// Metadata of a completed API call
+#[derive(Debug, Clone)]
+pub struct ResponseMetadata {
+    /// HTTP status code
+    pub status: StatusCode,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Server-assigned request ID, or the ID sent by the client if the
+    /// server did not echo one
+    pub request_id: Option<String>,
+    /// Number of attempts made, including the successful one
+    pub attempts: u32,
+    /// Total time spent, including retries and backoff
+    pub latency: Duration,
+    /// Duplicate requests sent by hedging
+    pub hedges: u32,
+    /// Whether the response came from a hedged duplicate
+    pub hedge_won: bool,
+    /// Whether the body was served from the response cache, either fresh
+    /// (with no attempts made) or confirmed by a `304 Not Modified`
+    pub from_cache: bool,
+    /// Name of the endpoint that served the response: `"primary"` for the
+    /// base URL or a failover endpoint's name; `None` for fresh cache hits
+    /// and responses from other origins
+    pub endpoint: Option<String>,
+}
+
+impl ResponseMetadata {
+    /// Rate limit quota reported with this response
+    pub fn rate_limit(&self) -> Option<RateLimitStatus> {
+        RateLimitStatus::from_headers(&self.headers)
+    }
+}
+
+/// A deserialized response body together with its metadata
+#[derive(Debug, Clone)]
+pub struct ApiResponse<T> {
+    data: T,
+    metadata: ResponseMetadata,
+}
+
+impl<T> ApiResponse<T> {
+    /// Create a new API response
+    pub fn new(data: T, metadata: ResponseMetadata) -> Self {
+        Self { data, metadata }
+    }
+
+    /// Deserialized response body
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Consume the response and return the body
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Consume the response and return the body and metadata
+    pub fn into_parts(self) -> (T, ResponseMetadata) {
+        (self.data, self.metadata)
+    }
+
+    /// Response metadata
+    pub fn metadata(&self) -> &ResponseMetadata {
+        &self.metadata
+    }
+
+    /// HTTP status code
+    pub fn status(&self) -> StatusCode {
+        self.metadata.status
+    }
+
+    /// Response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.metadata.headers
+    }
+
+    /// Request ID to quote in support tickets
+    pub fn request_id(&self) -> Option<&str> {
+        self.metadata.request_id.as_deref()
+    }
+
+    /// Number of attempts made, including the successful one
+    pub fn attempts(&self) -> u32 {
+        self.metadata.attempts
+    }
+
+    /// Total time spent, including retries and backoff
+    pub fn latency(&self) -> Duration {
+        self.metadata.latency
+    }
+
+    /// Duplicate requests sent by hedging
+    pub fn hedges(&self) -> u32 {
+        self.metadata.hedges
+    }
+
+    /// Whether the response came from a hedged duplicate
+    pub fn hedge_won(&self) -> bool {
+        self.metadata.hedge_won
+    }
+
+    /// Whether the body was served from the response cache
+    pub fn from_cache(&self) -> bool {
+        self.metadata.from_cache
+    }
+
+    /// Name of the endpoint that served the response
+    pub fn endpoint(&self) -> Option<&str> {
+        self.metadata.endpoint.as_deref()
+    }
+
+    /// Rate limit quota reported with this response
+    pub fn rate_limit(&self) -> Option<RateLimitStatus> {
+        self.metadata.rate_limit()
+    }
+
+    /// Transform the body, keeping the metadata
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ApiResponse<U> {
+        ApiResponse {
+            data: f(self.data),
+            metadata: self.metadata,
+        }
+    }
+}
+
+impl ApiResponse<HttpResponse> {
+    /// Deserialize the buffered body as JSON, keeping the metadata
+    pub(crate) fn json<T: DeserializeOwned>(self) -> Result<ApiResponse<T>> {
+        let data = self.data.json()?;
+        Ok(ApiResponse::new(data, self.metadata))
+    }
+}
+
+/// Records the metadata of calls made through resource clients
+///
+/// Resource methods return only the deserialized body. Attach a capture with
+/// [`RequestOptions::capture`](crate::request::RequestOptions::capture) to
+/// read the status, headers and request ID of the most recent call:
+///
+/// ```no_run
+/// # async fn run(client: stateset_client::Client) -> stateset_core::Result<()> {
+/// use stateset_client::{request::RequestOptions, response::ResponseCapture};
+///
+/// let capture = ResponseCapture::new();
+/// let order = client
+///     .orders()
+///     .with_options(RequestOptions::new().capture(capture.clone()))
+///     .get("ord_123")
+///     .await?;
+/// println!("request id: {:?}", capture.last().and_then(|m| m.request_id));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCapture {
+    last: Arc<Mutex<Option<ResponseMetadata>>>,
+}
+
+impl ResponseCapture {
+    /// Create an empty capture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metadata of the most recent successful call
+    pub fn last(&self) -> Option<ResponseMetadata> {
+        self.lock().clone()
+    }
+
+    pub(crate) fn record(&self, metadata: &ResponseMetadata) {
+        *self.lock() = Some(metadata.clone());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<ResponseMetadata>> {
+        match self.last.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_response_accessors() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", "req_123".parse().unwrap());
+
+        let response = HttpResponse::new(
+            Method::GET,
+            Url::parse("https://api.stateset.io/api/v1/orders").unwrap(),
+            StatusCode::OK,
+            headers,
+            r#"{"id": "ord_1"}"#,
+        );
+
+        assert_eq!(response.request_id(), Some("req_123"));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let value: serde_json::Value = response.json().unwrap();
+        assert_eq!(value["id"], "ord_1");
+    }
+}
@@ -1,15 +1,165 @@
 //! Retry policy implementation for StateSet SDK
+//!
+//! A [`RetryPolicy`] decides how often and how long to back off; its
+//! [`RetryClassifier`] decides whether a failed attempt may be retried at
+//! all, and an optional [`RetryBudget`] caps retries relative to successes so
+//! an outage is not amplified by every client retrying at once.
 
+use reqwest::Method;
+use stateset_core::Error;
+use std::fmt;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-/// Retry policy for HTTP requests
+/// How retry delays are randomised
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JitterStrategy {
+    /// Exponential backoff scaled by a random factor between 0.5 and 1.5
+    #[default]
+    Proportional,
+    /// A random delay between zero and the exponential backoff
+    Full,
+    /// A random delay between the initial delay and three times the previous
+    /// delay, capped at the maximum
+    Decorrelated,
+}
+
+/// What a [`RetryClassifier`] knows about a failed attempt
+#[derive(Debug)]
+pub struct RetryContext<'a> {
+    /// HTTP method of the request
+    pub method: &'a Method,
+    /// Whether repeating the request is safe: the method is idempotent or the
+    /// request carries an idempotency key
+    pub idempotent: bool,
+    /// Error of the failed attempt
+    pub error: &'a Error,
+    /// Zero-based number of the failed attempt
+    pub attempt: u32,
+}
+
+/// Decides whether a failed attempt should be retried
+///
+/// Attempt limits, deadlines and the retry budget are enforced separately;
+/// the classifier only judges whether the failure is worth retrying.
+/// Closures taking a [`RetryContext`] implement this trait.
+pub trait RetryClassifier: Send + Sync {
+    /// Whether the failed attempt should be retried
+    fn should_retry(&self, context: &RetryContext<'_>) -> bool;
+}
+
+impl<F> RetryClassifier for F
+where
+    F: Fn(&RetryContext<'_>) -> bool + Send + Sync,
+{
+    fn should_retry(&self, context: &RetryContext<'_>) -> bool {
+        self(context)
+    }
+}
+
+/// The default retry classification
+///
+/// Retries transient errors ([`Error::is_retryable`]) of requests that are
+/// safe to repeat. Requests that are not idempotent are only retried when the
+/// error shows they never reached the server.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRetryClassifier;
+
+impl RetryClassifier for DefaultRetryClassifier {
+    fn should_retry(&self, context: &RetryContext<'_>) -> bool {
+        context.error.is_retryable() && (context.idempotent || !context.error.request_sent())
+    }
+}
+
+/// A token bucket limiting retries to a share of successful requests
+///
+/// Every success deposits `ratio` tokens and every retry withdraws one; when
+/// fewer than one token is left, failures are returned without retrying.
+/// The bucket starts full. Clones share the same bucket, so one budget can
+/// be given to many clients.
 #[derive(Debug, Clone)]
+pub struct RetryBudget {
+    ratio: f64,
+    max_tokens: f64,
+    tokens: Arc<Mutex<f64>>,
+}
+
+impl RetryBudget {
+    /// Allow `ratio` retries per success, banking at most `max_tokens`
+    pub fn new(ratio: f64, max_tokens: u32) -> Self {
+        let max_tokens = f64::from(max_tokens);
+        Self {
+            ratio: ratio.max(0.0),
+            max_tokens,
+            tokens: Arc::new(Mutex::new(max_tokens)),
+        }
+    }
+
+    fn tokens(&self) -> std::sync::MutexGuard<'_, f64> {
+        match self.tokens.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Record a successful request
+    pub fn deposit(&self) {
+        let mut tokens = self.tokens();
+        *tokens = (*tokens + self.ratio).min(self.max_tokens);
+    }
+
+    /// Take a token for a retry, returning `false` if the budget is spent
+    pub fn try_withdraw(&self) -> bool {
+        let mut tokens = self.tokens();
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Retries currently available
+    pub fn available(&self) -> u32 {
+        *self.tokens() as u32
+    }
+}
+
+impl Default for RetryBudget {
+    /// One retry per ten successes, with up to ten retries banked
+    fn default() -> Self {
+        Self::new(0.1, 10)
+    }
+}
+
+/// Retry policy for HTTP requests
+#[derive(Clone)]
 pub struct RetryPolicy {
     pub max_attempts: u32,
     pub initial_delay: Duration,
     pub max_delay: Duration,
     pub multiplier: f64,
     pub jitter: bool,
+    /// How delays are randomised when `jitter` is enabled
+    pub jitter_strategy: JitterStrategy,
+    /// Decides which failures are retried
+    pub classifier: Arc<dyn RetryClassifier>,
+    /// Shared limit on retries, if any
+    pub budget: Option<RetryBudget>,
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .field("jitter_strategy", &self.jitter_strategy)
+            .field("budget", &self.budget)
+            .finish_non_exhaustive()
+    }
 }
 
 impl RetryPolicy {
@@ -26,6 +176,9 @@ impl RetryPolicy {
             max_delay,
             multiplier,
             jitter: true,
+            jitter_strategy: JitterStrategy::default(),
+            classifier: Arc::new(DefaultRetryClassifier),
+            budget: None,
         }
     }
 
@@ -35,21 +188,66 @@ impl RetryPolicy {
         self
     }
 
+    /// Randomise delays with the given strategy
+    pub fn with_jitter_strategy(mut self, strategy: JitterStrategy) -> Self {
+        self.jitter = true;
+        self.jitter_strategy = strategy;
+        self
+    }
+
+    /// Decide which failures are retried with a custom classifier
+    pub fn with_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Limit retries with a shared budget
+    pub fn with_budget(mut self, budget: RetryBudget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     /// Calculate the delay for a given attempt number
     pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
-        if attempt == 0 {
-            return self.initial_delay;
+        self.next_delay(attempt, None)
+    }
+
+    /// Calculate the delay after `attempt`, given the delay used before it
+    ///
+    /// Only decorrelated jitter depends on the previous delay; without one,
+    /// the un-jittered backoff of the previous attempt is assumed.
+    pub fn next_delay(&self, attempt: u32, previous: Option<Duration>) -> Duration {
+        use rand::Rng;
+
+        if !self.jitter {
+            return self.backoff(attempt);
         }
 
-        let base_delay = self.initial_delay.as_millis() as f64 * self.multiplier.powi(attempt as i32);
-        let delay = Duration::from_millis(base_delay as u64);
-        let capped_delay = std::cmp::min(delay, self.max_delay);
+        match self.jitter_strategy {
+            JitterStrategy::Proportional if attempt == 0 => self.initial_delay,
+            JitterStrategy::Proportional => self.add_jitter(self.backoff(attempt)),
+            JitterStrategy::Full => {
+                let ceiling = self.backoff(attempt).as_millis() as u64;
+                Duration::from_millis(rand::thread_rng().gen_range(0..=ceiling))
+            }
+            JitterStrategy::Decorrelated => {
+                let previous = previous.unwrap_or_else(|| self.backoff(attempt.saturating_sub(1)));
+                let floor = self.initial_delay.as_millis() as u64;
+                let ceiling = (previous.as_millis() as u64).saturating_mul(3).max(floor);
+                let delay = Duration::from_millis(rand::thread_rng().gen_range(floor..=ceiling));
+                delay.min(self.max_delay)
+            }
+        }
+    }
 
-        if self.jitter {
-            self.add_jitter(capped_delay)
-        } else {
-            capped_delay
+    /// Exponential backoff for an attempt, capped at the maximum delay
+    fn backoff(&self, attempt: u32) -> Duration {
+        if attempt == 0 {
+            return self.initial_delay.min(self.max_delay);
         }
+
+        let base_delay = self.initial_delay.as_millis() as f64 * self.multiplier.powi(attempt as i32);
+        std::cmp::min(Duration::from_millis(base_delay as u64), self.max_delay)
     }
 
     /// Add jitter to prevent thundering herd problems
@@ -160,4 +358,101 @@ mod tests {
         // but it's very unlikely with the jitter range we use
         assert_ne!(delay1, delay2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_jitter_strategies_stay_in_bounds() {
+        let policy = RetryPolicy::new(
+            5,
+            Duration::from_millis(100),
+            Duration::from_millis(1000),
+            2.0,
+        );
+
+        let full = policy.clone().with_jitter_strategy(JitterStrategy::Full);
+        let decorrelated = policy.with_jitter_strategy(JitterStrategy::Decorrelated);
+        for _ in 0..100 {
+            assert!(full.delay_for_attempt(2) <= Duration::from_millis(400));
+
+            let delay = decorrelated.next_delay(3, Some(Duration::from_millis(200)));
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(600));
+
+            let capped = decorrelated.next_delay(4, Some(Duration::from_millis(900)));
+            assert!(capped <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn test_retry_budget_tokens() {
+        let budget = RetryBudget::new(0.5, 2);
+        let shared = budget.clone();
+
+        assert_eq!(budget.available(), 2);
+        assert!(budget.try_withdraw());
+        assert!(shared.try_withdraw());
+        assert!(!budget.try_withdraw());
+
+        // Two successes earn one retry
+        budget.deposit();
+        assert!(!shared.try_withdraw());
+        shared.deposit();
+        assert!(budget.try_withdraw());
+
+        // Deposits never exceed the cap
+        for _ in 0..10 {
+            budget.deposit();
+        }
+        assert_eq!(shared.available(), 2);
+    }
+
+    #[test]
+    fn test_default_classifier() {
+        let classify = |method: &Method, idempotent: bool, error: &Error| {
+            DefaultRetryClassifier.should_retry(&RetryContext {
+                method,
+                idempotent,
+                error,
+                attempt: 0,
+            })
+        };
+
+        let unavailable = Error::ServiceUnavailable {
+            message: "down".to_string(),
+            retry_after: None,
+        };
+        let refused = Error::connection("Connection failed");
+        let reset = Error::network("Connection reset");
+
+        assert!(classify(&Method::GET, true, &unavailable));
+        assert!(classify(&Method::GET, true, &reset));
+        assert!(!classify(&Method::POST, false, &unavailable));
+        assert!(!classify(&Method::POST, false, &reset));
+        assert!(classify(&Method::POST, false, &refused));
+        assert!(!classify(&Method::GET, true, &Error::NotFound));
+    }
+
+    #[test]
+    fn test_closure_classifier() {
+        // Retry conflicts once, nothing else
+        let policy = RetryPolicy::default().with_classifier(|context: &RetryContext<'_>| {
+            context.attempt == 0 && context.error.status_code() == Some(409)
+        });
+        let conflict = Error::Conflict {
+            message: "locked".to_string(),
+            retry_after: None,
+        };
+        let unavailable = Error::ServiceUnavailable {
+            message: "down".to_string(),
+            retry_after: None,
+        };
+        let context = |error, attempt| RetryContext {
+            method: &Method::PUT,
+            idempotent: true,
+            error,
+            attempt,
+        };
+
+        assert!(policy.classifier.should_retry(&context(&conflict, 0)));
+        assert!(!policy.classifier.should_retry(&context(&conflict, 1)));
+        assert!(!policy.classifier.should_retry(&context(&unavailable, 0)));
+    }
+}
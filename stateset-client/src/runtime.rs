@@ -0,0 +1,262 @@
+//! Async runtime abstraction
+//!
+//! The client never names an executor directly: retry backoff, throttling
+//! delays and background work go through a [`Runtime`]. The default is
+//! [`TokioRuntime`] when the `tokio` feature is enabled and
+//! [`AsyncStdRuntime`] otherwise; other executors can be plugged in with
+//! [`Client::with_runtime`](crate::Client::with_runtime).
+//!
+//! reqwest performs its I/O on Tokio. Without the `tokio` feature, requests
+//! sent by the default transport run on a private Tokio reactor with one
+//! worker thread, so the client works from any executor.
+
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(any(feature = "tokio", feature = "async-std")))]
+compile_error!("stateset-client requires either the `tokio` or the `async-std` feature");
+
+/// Timers and task spawning for the client
+pub trait Runtime: Send + Sync {
+    /// Complete after `duration` has elapsed
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Run a future to completion in the background
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+}
+
+impl<R: Runtime + ?Sized> Runtime for Arc<R> {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        (**self).sleep(duration)
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        (**self).spawn(future)
+    }
+}
+
+/// Runtime backed by Tokio
+///
+/// Must be used from within a Tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl Runtime for TokioRuntime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+}
+
+/// Runtime backed by async-std
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "async-std")]
+impl Runtime for AsyncStdRuntime {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        async_std::task::spawn(future);
+    }
+}
+
+/// The runtime selected by the enabled features
+pub fn default_runtime() -> Arc<dyn Runtime> {
+    #[cfg(feature = "tokio")]
+    {
+        Arc::new(TokioRuntime)
+    }
+    #[cfg(not(feature = "tokio"))]
+    {
+        Arc::new(AsyncStdRuntime)
+    }
+}
+
+/// Run reqwest futures where a Tokio reactor is available
+#[cfg(not(feature = "tokio"))]
+pub(crate) mod io {
+    use futures::channel::mpsc;
+    use futures::{SinkExt, Stream, StreamExt};
+    use stateset_core::{Error, Result};
+    use std::future::Future;
+    use std::sync::OnceLock;
+
+    fn reactor() -> &'static tokio::runtime::Runtime {
+        static REACTOR: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+        REACTOR.get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(1)
+                .thread_name("stateset-io")
+                .enable_all()
+                .build()
+                .expect("failed to start the stateset I/O reactor")
+        })
+    }
+
+    /// Run `future` on the I/O reactor and wait for its output
+    pub(crate) async fn run<F>(future: F) -> Result<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        reactor()
+            .spawn(future)
+            .await
+            .map_err(|e| Error::Other(format!("I/O task failed: {}", e)))
+    }
+
+    /// Forward a body stream polled on the I/O reactor
+    pub(crate) fn forward<S, T>(stream: S) -> impl Stream<Item = T> + Send + 'static
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (mut sender, receiver) = mpsc::channel(8);
+
+        reactor().spawn(async move {
+            futures::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                // Stop reading once the receiving side is gone
+                if sender.send(item).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use crate::Client;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use http::Method;
+    use stateset_core::Config;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn config(base_url: &str) -> Config {
+        Config::builder()
+            .base_url(base_url)
+            .retry_attempts(2)
+            .retry_delay(Duration::from_millis(1))
+            .max_retry_delay(Duration::from_millis(5))
+            .build()
+            .unwrap()
+    }
+
+    fn json(status: u16, body: serde_json::Value) -> stateset_core::Result<http::Response<Bytes>> {
+        Ok(http::Response::builder()
+            .status(status)
+            .header("content-type", "application/json")
+            .body(Bytes::from(body.to_string()))
+            .unwrap())
+    }
+
+    /// Retries and pagination, identical on every runtime
+    async fn exercise(client: Client) {
+        let order: serde_json::Value = client.get("/api/v1/orders/ord_1").await.unwrap();
+        assert_eq!(order["id"], "ord_1");
+
+        let items: Vec<serde_json::Value> = client
+            .stream("/api/v1/items")
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<stateset_core::Result<_>>()
+            .unwrap();
+        assert_eq!(items.len(), 3);
+    }
+
+    fn memory_client() -> Client {
+        let calls = Arc::new(AtomicU32::new(0));
+        let transport = MemoryTransport::new()
+            .route(Method::GET, "/api/v1/orders/{id}", move |_| {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => json(503, serde_json::json!({"message": "unavailable"})),
+                    _ => json(200, serde_json::json!({"id": "ord_1"})),
+                }
+            })
+            .route(Method::GET, "/api/v1/items", |request| match request.url().query() {
+                Some("page=2") => json(200, serde_json::json!({"data": [{"n": 3}]})),
+                _ => json(200, serde_json::json!({
+                    "data": [{"n": 1}, {"n": 2}],
+                    "next_page": "/api/v1/items?page=2",
+                })),
+            });
+
+        Client::with_transport(config("https://api.stateset.io"), transport).unwrap()
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_runtime() {
+        exercise(memory_client()).await;
+
+        let start = std::time::Instant::now();
+        TokioRuntime.sleep(Duration::from_millis(5)).await;
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_async_std_runtime() {
+        async_std::task::block_on(async {
+            let client = memory_client().with_runtime(AsyncStdRuntime);
+            exercise(client).await;
+
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            AsyncStdRuntime.spawn(Box::pin(async move {
+                AsyncStdRuntime.sleep(Duration::from_millis(1)).await;
+                let _ = sender.send(());
+            }));
+            receiver.await.unwrap();
+        });
+    }
+
+    #[cfg(all(feature = "async-std", not(feature = "tokio")))]
+    #[test]
+    fn test_reqwest_outside_tokio() {
+        let mut server = mockito::Server::new();
+        let flaky = server
+            .mock("GET", "/api/v1/orders/ord_1")
+            .with_status(503)
+            .expect(1)
+            .create();
+        let order = server
+            .mock("GET", "/api/v1/orders/ord_1")
+            .with_status(200)
+            .with_body(r#"{"id": "ord_1"}"#)
+            .create();
+        let export = server
+            .mock("GET", "/api/v1/exports/1")
+            .with_body("a,b\n1,2\n")
+            .create();
+
+        let client = Client::with_config(config(&server.url())).unwrap();
+        async_std::task::block_on(async {
+            let body: serde_json::Value = client.get("/api/v1/orders/ord_1").await.unwrap();
+            assert_eq!(body["id"], "ord_1");
+
+            let download = client.download("/api/v1/exports/1").await.unwrap();
+            assert_eq!(download.bytes().await.unwrap(), Bytes::from("a,b\n1,2\n"));
+        });
+
+        flaky.assert();
+        order.assert();
+        export.assert();
+    }
+}
@@ -0,0 +1,349 @@
+//! tower integration for the HTTP core
+//!
+//! [`HttpService`] exposes the reqwest-backed transport as a
+//! `tower::Service<http::Request<Body>>`, so standard layers (timeouts,
+//! concurrency limits, load shedding, custom auth) can be stacked on top of it.
+//! The composed service is handed back to the SDK with [`Client::with_service`].
+//!
+//! ```no_run
+//! use stateset_client::{service::HttpService, Client};
+//! use stateset_core::Config;
+//! use std::time::Duration;
+//! use tower::ServiceBuilder;
+//!
+//! # fn main() -> stateset_core::Result<()> {
+//! let config = Config::with_base_url("https://api.stateset.io")?;
+//! let service = ServiceBuilder::new()
+//!     .load_shed()
+//!     .timeout(Duration::from_secs(10))
+//!     .service(HttpService::new(&config)?);
+//!
+//! let client = Client::with_service(config, service)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::response::HttpResponse;
+use crate::transport::Transport;
+use crate::Client;
+use futures::future::BoxFuture;
+use http_body_util::BodyExt;
+use reqwest::{Client as ReqwestClient, Method, RequestBuilder};
+use stateset_core::{Config, Error, Result};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::util::BoxCloneSyncService;
+use tower::{BoxError, Service, ServiceExt};
+use url::Url;
+
+pub use reqwest::Body;
+
+/// Type-erased HTTP service used by [`Client`]
+pub(crate) type BoxHttpService =
+    BoxCloneSyncService<http::Request<Body>, http::Response<Body>, Error>;
+
+/// The reqwest-backed HTTP core as a tower service
+///
+/// Sends a single request with no retries or middleware; those are applied by
+/// [`Client`] around whatever service it is given.
+#[derive(Debug, Clone)]
+pub struct HttpService {
+    http: ReqwestClient,
+    timeout: Duration,
+}
+
+impl HttpService {
+    /// Create a new HTTP service from a configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            http: crate::build_http_client(config)?,
+            timeout: config.timeout,
+        })
+    }
+
+    pub(crate) fn from_client(http: ReqwestClient, timeout: Duration) -> Self {
+        Self { http, timeout }
+    }
+}
+
+impl Service<http::Request<Body>> for HttpService {
+    type Response = http::Response<Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<http::Response<Body>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let http = self.http.clone();
+        let timeout = self.timeout;
+
+        Box::pin(async move {
+            let request = reqwest::Request::try_from(request).map_err(|e| Error::InvalidRequest {
+                message: format!("Failed to convert request: {}", e),
+                parameter: None,
+            })?;
+            let response = http
+                .execute(request)
+                .await
+                .map_err(|e| crate::transport_error(e, timeout))?;
+            Ok(response.into())
+        })
+    }
+}
+
+/// Box a user-supplied service, normalising its errors into SDK errors
+pub(crate) fn boxed<S>(service: S, timeout: Duration) -> BoxHttpService
+where
+    S: Service<http::Request<Body>, Response = http::Response<Body>> + Clone + Send + Sync + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    BoxCloneSyncService::new(service.map_err(move |e| into_error(e.into(), timeout)))
+}
+
+/// Convert a tower error into an SDK error
+fn into_error(error: BoxError, timeout: Duration) -> Error {
+    let error = match error.downcast::<Error>() {
+        Ok(error) => return *error,
+        Err(error) => error,
+    };
+    let error = match error.downcast::<reqwest::Error>() {
+        Ok(error) => return crate::transport_error(*error, timeout),
+        Err(error) => error,
+    };
+
+    if error.is::<tower::timeout::error::Elapsed>() {
+        Error::timeout(timeout, "http_request")
+    } else if error.is::<tower::load_shed::error::Overloaded>() {
+        Error::ServiceUnavailable {
+            message: "Request shed by load-shedding layer".to_string(),
+            retry_after: None,
+        }
+    } else {
+        Error::network(error.to_string())
+    }
+}
+
+/// Transport that sends requests through a boxed tower service
+pub(crate) struct ServiceTransport(BoxHttpService);
+
+impl Transport for ServiceTransport {
+    fn send(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse>> {
+        let service = self.0.clone();
+
+        Box::pin(async move {
+            let method = request.method().clone();
+            let url = request.url().clone();
+            let request = http::Request::try_from(request).map_err(|e| Error::InvalidRequest {
+                message: format!("Failed to convert request: {}", e),
+                parameter: None,
+            })?;
+
+            let response = service.oneshot(request).await?;
+            from_http(method, url, response).await
+        })
+    }
+}
+
+/// Buffer an `http::Response` into an [`HttpResponse`]
+async fn from_http(method: Method, url: Url, response: http::Response<Body>) -> Result<HttpResponse> {
+    let (parts, body) = response.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_err(|e| Error::network(format!("Failed to read response body: {}", e)))?
+        .to_bytes();
+
+    Ok(HttpResponse::new(method, url, parts.status, parts.headers, body))
+}
+
+impl From<HttpResponse> for http::Response<Body> {
+    fn from(response: HttpResponse) -> Self {
+        let mut builder = http::Response::builder().status(response.status());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().clone();
+        }
+        builder
+            .body(Body::from(response.body().clone()))
+            .expect("status and headers come from a valid response")
+    }
+}
+
+impl Client {
+    /// Create a client that sends requests through a custom tower service
+    ///
+    /// Middleware, authentication and retries still run in the client; the
+    /// service replaces only the underlying HTTP transport (see
+    /// [`Client::with_transport`]). Use
+    /// [`HttpService`] as the innermost service to keep the default transport.
+    pub fn with_service<S>(config: Config, service: S) -> Result<Self>
+    where
+        S: Service<http::Request<Body>, Response = http::Response<Body>>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
+        let timeout = config.timeout;
+        let mut client = Self::with_config(config)?;
+        let inner = std::sync::Arc::make_mut(&mut client.inner);
+        inner.transport = std::sync::Arc::new(ServiceTransport(boxed(service, timeout)));
+        Ok(client)
+    }
+
+    /// The default HTTP transport of this client as a tower service
+    pub fn http_service(&self) -> HttpService {
+        HttpService::from_client(self.inner.http.clone(), self.inner.config.timeout)
+    }
+}
+
+/// The full client pipeline (authentication, default headers, middleware and
+/// retries) as a tower service
+impl Service<http::Request<Body>> for Client {
+    type Response = http::Response<Body>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<http::Response<Body>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let client = self.clone();
+
+        Box::pin(async move {
+            let mut request = reqwest::Request::try_from(request).map_err(|e| Error::InvalidRequest {
+                message: format!("Failed to convert request: {}", e),
+                parameter: None,
+            })?;
+
+            // Fill in the client's headers without overriding the caller's
+            let defaults = client
+                .decorate(client.inner.http.request(request.method().clone(), request.url().clone()))
+                .build()
+                .map_err(|e| Error::InvalidRequest {
+                    message: format!("Failed to build request: {}", e),
+                    parameter: None,
+                })?;
+            for (name, value) in defaults.headers() {
+                if !request.headers().contains_key(name) {
+                    request.headers_mut().insert(name, value.clone());
+                }
+            }
+
+            let builder = RequestBuilder::from_parts(client.inner.http.clone(), request);
+            let response = client.execute_raw(builder).await?;
+            Ok(response.into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceBuilder;
+
+    #[test]
+    fn test_error_mapping() {
+        let timeout = Duration::from_secs(5);
+
+        let error = into_error(Box::new(tower::timeout::error::Elapsed::new()), timeout);
+        assert!(matches!(error, Error::Timeout { duration, .. } if duration == timeout));
+
+        let error = into_error(Box::new(tower::load_shed::error::Overloaded::new()), timeout);
+        assert!(matches!(error, Error::ServiceUnavailable { .. }));
+
+        let error = into_error(Box::new(Error::NotFound), timeout);
+        assert!(matches!(error, Error::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_client_with_layered_service() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/orders/1")
+            .match_header("x-company-auth", "secret")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_body(r#"{"id": "1"}"#)
+            .create_async()
+            .await;
+
+        let config = Config::with_base_url(server.url()).unwrap();
+        let service = ServiceBuilder::new()
+            .load_shed()
+            .concurrency_limit(4)
+            .timeout(Duration::from_secs(5))
+            .map_request(|mut request: http::Request<Body>| {
+                request
+                    .headers_mut()
+                    .insert("x-company-auth", "secret".parse().unwrap());
+                request
+            })
+            .service(HttpService::new(&config).unwrap());
+
+        let client = Client::with_service(config, service)
+            .unwrap()
+            .authenticate(stateset_auth::Credentials::bearer("token"));
+
+        let body: serde_json::Value = client.get("/api/v1/orders/1").await.unwrap();
+        assert_eq!(body["id"], "1");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_client_as_service() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/products/1")
+            .match_header("authorization", "Bearer token")
+            .with_status(200)
+            .with_body(r#"{"id": "1"}"#)
+            .create_async()
+            .await;
+
+        let client = Client::new(server.url())
+            .unwrap()
+            .authenticate(stateset_auth::Credentials::bearer("token"));
+
+        let request = http::Request::get(format!("{}/api/v1/products/1", server.url()))
+            .body(Body::from(""))
+            .unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_service_keeps_caller_headers() {
+        let transport = std::sync::Arc::new(crate::transport::MemoryTransport::new().fallback(|_| {
+            Ok(http::Response::builder().body(bytes::Bytes::from("{}")).unwrap())
+        }));
+        let config = Config::builder()
+            .base_url("https://api.stateset.io")
+            .default_header("X-Tenant", "acme")
+            .build()
+            .unwrap();
+        let client = Client::with_transport(config, transport.clone())
+            .unwrap()
+            .authenticate(stateset_auth::Credentials::bearer("token"));
+
+        let request = http::Request::get("https://api.stateset.io/api/v1/products/1")
+            .header("authorization", "Bearer caller")
+            .header("x-request-id", "req_1")
+            .body(Body::from(""))
+            .unwrap();
+        client.oneshot(request).await.unwrap();
+
+        let headers = &transport.requests()[0].headers;
+        let values = |name: &str| headers.get_all(name).iter().collect::<Vec<_>>();
+        assert_eq!(values("authorization"), ["Bearer caller"]);
+        assert_eq!(values("x-request-id"), ["req_1"]);
+        assert_eq!(values("x-tenant"), ["acme"]);
+    }
+}
@@ -0,0 +1,365 @@
+//! Tracing spans and W3C trace context propagation
+//!
+//! Every logical call runs in a `stateset.request` span, every attempt in a
+//! child `stateset.attempt` span and every hedged duplicate in a
+//! `stateset.hedge` span below its attempt. All carry the method and the
+//! route template (see [`route_template`]); the call span adds the request
+//! ID, the final status and the number of attempts, and the others their own
+//! status, retry number and endpoint. Field names follow the OpenTelemetry HTTP client
+//! conventions, so `tracing-opentelemetry` exports them as-is.
+//!
+//! With [`Config::propagate_trace_context`](stateset_core::Config) enabled
+//! (it is off by default), each call sends a `traceparent` header. It is
+//! generated once per call unless the request already has one. With the `opentelemetry` feature and
+//! an OpenTelemetry layer installed, each attempt instead sends the context
+//! of its own span, with the `tracestate` of the trace, so StateSet calls
+//! join the caller's distributed trace.
+
+use crate::download::Download;
+use crate::response::HttpResponse;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+use stateset_core::Error;
+use tracing::field::Empty;
+use tracing::Span;
+
+/// W3C trace context header
+pub const TRACEPARENT: &str = "traceparent";
+
+/// W3C vendor-specific trace state header
+pub const TRACESTATE: &str = "tracestate";
+
+/// Routes of the resource clients relative to the versioned API root, with
+/// identifiers as `{id}`
+///
+/// Kept in step with the paths the clients in [`crate::resources`] build by
+/// `test_routes_match_resource_clients`.
+const ROUTES: &[&str] = &[
+    "analytics/cohort", "analytics/customers", "analytics/dashboard/realtime",
+    "analytics/funnel/conversion", "analytics/inventory", "analytics/products", "analytics/query",
+    "analytics/reports", "analytics/reports/{id}", "analytics/reports/{id}/export",
+    "analytics/sales", "analytics/trends/revenue",
+    "boms", "boms/by-product/{id}", "boms/explosion/{id}", "boms/{id}", "boms/{id}/approve",
+    "boms/{id}/cost-analysis",
+    "carts", "carts/abandoned", "carts/analytics", "carts/token/{id}", "carts/{id}",
+    "carts/{id}/clear", "carts/{id}/convert", "carts/{id}/coupons", "carts/{id}/coupons/{id}",
+    "carts/{id}/items", "carts/{id}/items/{id}",
+    "checkouts", "checkouts/abandoned", "checkouts/abandonment-analysis", "checkouts/token/{id}",
+    "checkouts/{id}", "checkouts/{id}/cancel", "checkouts/{id}/complete", "checkouts/{id}/coupons",
+    "checkouts/{id}/gift-cards", "checkouts/{id}/shipping-rates", "checkouts/{id}/step",
+    "inventory/adjust", "inventory/items/{id}/levels", "inventory/items/{id}/locations/{id}/level",
+    "inventory/reservations", "inventory/reservations/{id}", "inventory/reservations/{id}/cancel",
+    "orders", "orders/analytics", "orders/batch", "orders/{id}", "orders/{id}/cancel",
+    "orders/{id}/fulfill", "orders/{id}/refund", "orders/{id}/returns", "orders/{id}/shipments",
+    "products", "products/by-brand/{id}", "products/by-category/{id}", "products/search",
+    "products/sku/{id}", "products/{id}", "products/{id}/documents", "products/{id}/duplicate",
+    "products/{id}/images",
+    "returns", "returns/{id}", "returns/{id}/approve", "returns/{id}/process",
+    "returns/{id}/receive", "returns/{id}/reject",
+    "shipments", "shipments/rates", "shipments/tracking", "shipments/{id}", "shipments/{id}/cancel",
+    "shipments/{id}/deliver", "shipments/{id}/label", "shipments/{id}/label/download",
+    "shipments/{id}/ship", "shipments/{id}/tracking-events",
+    "warranties", "warranties/analytics", "warranties/by-customer/{id}",
+    "warranties/by-product/{id}", "warranties/{id}", "warranties/{id}/activate",
+    "warranties/{id}/transfer", "warranties/{id}/void",
+    "warranty-claims", "warranty-claims/{id}", "warranty-claims/{id}/approve",
+    "warranty-claims/{id}/attachments", "warranty-claims/{id}/deny",
+    "work-orders", "work-orders/analytics", "work-orders/batch", "work-orders/by-asset/{id}",
+    "work-orders/by-assignee/{id}", "work-orders/by-customer/{id}", "work-orders/{id}",
+    "work-orders/{id}/assign", "work-orders/{id}/attachments", "work-orders/{id}/cancel",
+    "work-orders/{id}/complete", "work-orders/{id}/hold", "work-orders/{id}/resume",
+    "work-orders/{id}/start",
+];
+
+/// Route of a path with identifier segments replaced by `{id}`
+///
+/// Paths of the resource clients map to their route, preferring sub-routes
+/// over identifiers, so `/api/v1/orders/ord_123/cancel` becomes
+/// `/api/v1/orders/{id}/cancel` and `/api/v1/products/sku/level` becomes
+/// `/api/v1/products/sku/{id}`. In other paths every segment after the
+/// resource name is an identifier unless some route uses it as a sub-route.
+/// The query string is dropped.
+pub fn route_template(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    let group = crate::request::endpoint_group(path);
+    let segments: Vec<&str> = path.split('/').collect();
+    let Some(start) = segments.iter().position(|segment| !group.is_empty() && *segment == group) else {
+        return path.to_string();
+    };
+    let (prefix, rest) = segments.split_at(start);
+
+    let matches = |route: &[&str]| {
+        route.len() == rest.len()
+            && route
+                .iter()
+                .zip(rest)
+                .all(|(part, segment)| part == segment || (*part == "{id}" && !segment.is_empty()))
+    };
+    // Comparing literal flags in order prefers the earliest sub-route
+    let route = ROUTES
+        .iter()
+        .map(|route| route.split('/').collect::<Vec<_>>())
+        .filter(|route| matches(route))
+        .max_by_key(|route| route.iter().map(|part| *part != "{id}").collect::<Vec<_>>());
+
+    let route = route.unwrap_or_else(|| {
+        let is_sub_route = |segment: &str| {
+            ROUTES.iter().any(|route| route.split('/').skip(1).any(|part| part == segment))
+        };
+        let ids = rest[1..]
+            .iter()
+            .map(|segment| match segment.is_empty() || is_sub_route(segment) {
+                true => *segment,
+                false => "{id}",
+            });
+        std::iter::once(rest[0]).chain(ids).collect()
+    });
+    prefix.iter().copied().chain(route).collect::<Vec<_>>().join("/")
+}
+
+/// Span of one logical call
+pub(crate) fn request_span(request: &reqwest::Request) -> Span {
+    let method = request.method().as_str();
+    let route = route_template(request.url().path());
+    let request_id = request
+        .headers()
+        .get("X-Request-ID")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "stateset.request",
+        otel.name = %format_args!("{} {}", method, route),
+        otel.status_code = Empty,
+        http.request.method = method,
+        url.template = %route,
+        stateset.request_id = request_id,
+        stateset.attempts = Empty,
+        http.response.status_code = Empty,
+        error.type = Empty,
+    )
+}
+
+/// Span of one attempt; `resend_count` is zero for the first
+pub(crate) fn attempt_span(request: &reqwest::Request, resend_count: u32) -> Span {
+    let method = request.method().as_str();
+    let route = route_template(request.url().path());
+
+    tracing::info_span!(
+        "stateset.attempt",
+        otel.name = %format_args!("{} {}", method, route),
+        otel.kind = "client",
+        otel.status_code = Empty,
+        http.request.method = method,
+        url.template = %route,
+        http.request.resend_count = resend_count,
+        stateset.endpoint = Empty,
+        server.address = Empty,
+        http.response.status_code = Empty,
+        error.type = Empty,
+    )
+}
+
+/// Span of a hedged duplicate, a child of the attempt it duplicates
+pub(crate) fn hedge_span(request: &reqwest::Request, hedge: u32) -> Span {
+    let method = request.method().as_str();
+    let route = route_template(request.url().path());
+
+    tracing::info_span!(
+        "stateset.hedge",
+        otel.name = %format_args!("{} {}", method, route),
+        otel.kind = "client",
+        otel.status_code = Empty,
+        http.request.method = method,
+        url.template = %route,
+        stateset.hedge = hedge,
+        stateset.endpoint = Empty,
+        server.address = Empty,
+        http.response.status_code = Empty,
+        error.type = Empty,
+    )
+}
+
+/// Record the response status on a span
+pub(crate) fn record_status(span: &Span, status: StatusCode) {
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_client_error() || status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+        span.record("error.type", status.as_str());
+    }
+}
+
+/// Record a failure on a span
+pub(crate) fn record_error(span: &Span, error: &Error) {
+    span.record("otel.status_code", "ERROR");
+    match error.status_code() {
+        Some(status) => {
+            span.record("http.response.status_code", status);
+            span.record("error.type", status);
+        }
+        None => {
+            span.record("error.type", error.kind());
+        }
+    }
+}
+
+/// Results whose status is recorded on the call span
+pub(crate) trait Traced {
+    fn status(&self) -> StatusCode;
+}
+
+impl Traced for HttpResponse {
+    fn status(&self) -> StatusCode {
+        HttpResponse::status(self)
+    }
+}
+
+impl Traced for Download {
+    fn status(&self) -> StatusCode {
+        Download::status(self)
+    }
+}
+
+impl<T: Traced> Traced for (T, bool) {
+    fn status(&self) -> StatusCode {
+        self.0.status()
+    }
+}
+
+/// Give a call a `traceparent` unless it already has one
+///
+/// The generated context is unsampled, leaving the sampling decision to the
+/// server.
+pub(crate) fn start(request: &mut reqwest::Request) {
+    if request.headers().contains_key(TRACEPARENT) {
+        return;
+    }
+
+    let trace_id = uuid::Uuid::new_v4().simple();
+    let parent_id = rand::random::<u64>().max(1);
+    let traceparent = format!("00-{}-{:016x}-00", trace_id, parent_id);
+    if let Ok(value) = HeaderValue::from_str(&traceparent) {
+        request.headers_mut().insert(TRACEPARENT, value);
+    }
+}
+
+/// Send the trace context of `span` with an attempt
+///
+/// Without the `opentelemetry` feature, or if `span` is not exported through
+/// OpenTelemetry, the context set by [`start`] is kept.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn inject(request: &mut reqwest::Request, span: &Span) {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let context = span.context();
+    let span = context.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        return;
+    }
+
+    let traceparent = format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8()
+    );
+    let tracestate = span_context.trace_state().header();
+    let headers = request.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&traceparent) {
+        headers.insert(TRACEPARENT, value);
+    }
+    match HeaderValue::from_str(&tracestate) {
+        Ok(value) if !tracestate.is_empty() => {
+            headers.insert(TRACESTATE, value);
+        }
+        _ => {
+            headers.remove(TRACESTATE);
+        }
+    }
+}
+
+/// Send the trace context of `span` with an attempt
+///
+/// Without the `opentelemetry` feature the context set by [`start`] is kept.
+#[cfg(not(feature = "opentelemetry"))]
+pub(crate) fn inject(_request: &mut reqwest::Request, _span: &Span) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_template() {
+        assert_eq!(route_template("/api/v1/orders/ord_123/cancel"), "/api/v1/orders/{id}/cancel");
+        assert_eq!(route_template("/api/v2/products/sku/WIDGET-1"), "/api/v2/products/sku/{id}");
+        assert_eq!(
+            route_template("/gateway/api/v1/checkouts/abandonment-analysis?start=2024"),
+            "/gateway/api/v1/checkouts/abandonment-analysis"
+        );
+        assert_eq!(route_template("/api/v1/orders"), "/api/v1/orders");
+        assert_eq!(route_template("/api/v1/products/sku/WIDGET"), "/api/v1/products/sku/{id}");
+        assert_eq!(route_template("/api/v1/carts/token/abcdef"), "/api/v1/carts/token/{id}");
+        assert_eq!(
+            route_template("/api/v1/inventory/items/widget/locations/main/level"),
+            "/api/v1/inventory/items/{id}/locations/{id}/level"
+        );
+
+        // Identifiers that look like sub-routes stay identifiers
+        assert_eq!(route_template("/api/v1/products/sku/level"), "/api/v1/products/sku/{id}");
+        assert_eq!(route_template("/api/v1/orders/export"), "/api/v1/orders/{id}");
+        assert_eq!(route_template("/api/v1/carts/items/items/coupons"), "/api/v1/carts/{id}/items/{id}");
+
+        // Paths no client builds fall back to known sub-route names
+        assert_eq!(route_template("/api/v1/orders/ord_1/notes"), "/api/v1/orders/{id}/{id}");
+        assert_eq!(route_template("/api/v1/orders/ord_1/cancel/now"), "/api/v1/orders/{id}/cancel/{id}");
+    }
+
+    #[test]
+    fn test_routes_match_resource_clients() {
+        let sources = [
+            include_str!("resources/analytics.rs"),
+            include_str!("resources/boms.rs"),
+            include_str!("resources/carts.rs"),
+            include_str!("resources/checkouts.rs"),
+            include_str!("resources/inventory.rs"),
+            include_str!("resources/orders.rs"),
+            include_str!("resources/products.rs"),
+            include_str!("resources/returns.rs"),
+            include_str!("resources/shipments.rs"),
+            include_str!("resources/warranties.rs"),
+            include_str!("resources/work_orders.rs"),
+        ];
+
+        // The first string literal passed to every `api_path` call
+        let mut built = std::collections::BTreeSet::new();
+        for source in sources {
+            for call in source.split("api_path(").skip(1) {
+                let literal = call.split('"').nth(1).unwrap();
+                let path = literal.split('?').next().unwrap();
+                built.insert(path.trim_start_matches('/').replace("{}", "{id}"));
+            }
+        }
+        let routes: std::collections::BTreeSet<_> = ROUTES.iter().map(|route| route.to_string()).collect();
+        assert_eq!(built, routes);
+
+        for route in ROUTES {
+            let path = format!("/api/v1/{}", route.replace("{id}", "id_1"));
+            assert_eq!(route_template(&path), format!("/api/v1/{}", route), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_start_keeps_existing_traceparent() {
+        let client = reqwest::Client::new();
+        let mut request = client.get("https://api.stateset.io/api/v1/orders").build().unwrap();
+        start(&mut request);
+        let generated = request.headers()[TRACEPARENT].to_str().unwrap().to_string();
+        let parts: Vec<_> = generated.split('-').collect();
+        assert_eq!(parts.iter().map(|part| part.len()).collect::<Vec<_>>(), [2, 32, 16, 2]);
+
+        start(&mut request);
+        assert_eq!(request.headers()[TRACEPARENT], generated.as_str());
+    }
+}
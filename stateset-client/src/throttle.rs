@@ -0,0 +1,304 @@
+//! Adaptive throttling driven by server rate-limit headers
+//!
+//! Every response is scanned for `X-RateLimit-*`, IETF `RateLimit-*` and the
+//! structured `RateLimit` / `RateLimit-Policy` headers. The latest quota is
+//! kept per client (shared across clones) and used to pace requests once the
+//! remaining budget runs low, instead of waiting for a 429.
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Fraction of the limit below which requests are paced
+const LOW_WATER_FRACTION: f64 = 0.1;
+
+/// Values above this are treated as Unix timestamps rather than delta seconds
+const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// Server-reported rate limit quota
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitStatus {
+    /// Requests allowed in the current window, if reported
+    pub limit: Option<u32>,
+    /// Requests remaining in the current window
+    pub remaining: u32,
+    /// When the current window resets, if reported
+    pub reset_at: Option<DateTime<Utc>>,
+    /// When these values were received
+    pub observed_at: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    /// Time left until the window resets
+    pub fn reset_after(&self) -> Option<Duration> {
+        self.reset_at
+            .map(|reset| (reset - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+
+    /// Parse the quota from response headers, if the server sent any
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let now = Utc::now();
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        // Structured field: `RateLimit: limit=100, remaining=50, reset=30` or
+        // the newer `RateLimit: "default";r=50;t=30` with `RateLimit-Policy`
+        let structured = header("ratelimit").map(parse_structured).unwrap_or_default();
+        let policy_limit = header("ratelimit-policy")
+            .map(parse_structured)
+            .and_then(|params| params.limit);
+
+        let remaining = header("x-ratelimit-remaining")
+            .or_else(|| header("ratelimit-remaining"))
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .or(structured.remaining)?;
+
+        let limit = header("x-ratelimit-limit")
+            .or_else(|| header("ratelimit-limit"))
+            .and_then(first_number)
+            .or(structured.limit)
+            .or(policy_limit);
+
+        let reset_at = header("x-ratelimit-reset")
+            .or_else(|| header("ratelimit-reset"))
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .or(structured.reset)
+            .map(|reset| reset_time(reset, now));
+
+        Some(Self {
+            limit,
+            remaining,
+            reset_at,
+            observed_at: now,
+        })
+    }
+}
+
+/// Parameters extracted from a structured `RateLimit` header
+#[derive(Debug, Default)]
+struct StructuredParams {
+    limit: Option<u32>,
+    remaining: Option<u32>,
+    reset: Option<u64>,
+}
+
+fn parse_structured(value: &str) -> StructuredParams {
+    let mut params = StructuredParams::default();
+
+    for item in value.split([',', ';']) {
+        let Some((key, value)) = item.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim() {
+            "limit" | "q" => params.limit = value.parse().ok(),
+            "remaining" | "r" => params.remaining = value.parse().ok(),
+            "reset" | "t" => params.reset = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    params
+}
+
+/// Parse the leading number of a header such as `100, 100;w=60`
+fn first_number(value: &str) -> Option<u32> {
+    value
+        .split([',', ';'])
+        .next()
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Interpret a reset value as either delta seconds or a Unix timestamp
+fn reset_time(reset: u64, now: DateTime<Utc>) -> DateTime<Utc> {
+    if reset >= EPOCH_THRESHOLD {
+        DateTime::from_timestamp(reset as i64, 0).unwrap_or(now)
+    } else {
+        now + chrono::Duration::seconds(reset as i64)
+    }
+}
+
+/// Parse a `Retry-After` value in delta-seconds or HTTP-date form
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| (date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Live rate limit budget shared by clones of a client
+#[derive(Debug, Default)]
+pub(crate) struct AdaptiveThrottle {
+    status: Mutex<Option<RateLimitStatus>>,
+}
+
+impl AdaptiveThrottle {
+    /// Record the quota reported by a response
+    pub(crate) fn observe(&self, headers: &HeaderMap) {
+        if let Some(status) = RateLimitStatus::from_headers(headers) {
+            *self.lock() = Some(status);
+        }
+    }
+
+    /// Latest known quota
+    pub(crate) fn status(&self) -> Option<RateLimitStatus> {
+        self.lock().clone()
+    }
+
+    /// Reserve one request from the budget and return how long to wait first
+    ///
+    /// Requests go out unpaced while the budget is healthy. Below the low-water
+    /// mark the remaining requests are spread evenly until the window resets,
+    /// and once the budget is exhausted callers wait for the reset.
+    pub(crate) fn reserve(&self) -> Option<Duration> {
+        let mut guard = self.lock();
+        let status = guard.as_mut()?;
+        let reset_after = status.reset_after().filter(|d| !d.is_zero())?;
+
+        let remaining = status.remaining;
+        status.remaining = remaining.saturating_sub(1);
+
+        if remaining == 0 {
+            return Some(reset_after);
+        }
+
+        let low_water = status
+            .limit
+            .map(|limit| ((limit as f64 * LOW_WATER_FRACTION).ceil() as u32).max(1))
+            .unwrap_or(1);
+        if remaining > low_water {
+            return None;
+        }
+
+        Some(reset_after / (remaining + 1))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<RateLimitStatus>> {
+        match self.status.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parse_x_ratelimit_headers() {
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "42"),
+            ("x-ratelimit-reset", "30"),
+        ]))
+        .unwrap();
+
+        assert_eq!(status.limit, Some(100));
+        assert_eq!(status.remaining, 42);
+        let reset_after = status.reset_after().unwrap();
+        assert!(reset_after <= Duration::from_secs(30));
+        assert!(reset_after >= Duration::from_secs(28));
+    }
+
+    #[test]
+    fn test_parse_epoch_reset() {
+        let reset = Utc::now().timestamp() + 60;
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("x-ratelimit-remaining", "1"),
+            ("x-ratelimit-reset", &reset.to_string()),
+        ]))
+        .unwrap();
+
+        assert!(status.reset_after().unwrap() > Duration::from_secs(55));
+    }
+
+    #[test]
+    fn test_parse_ietf_headers() {
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("ratelimit-limit", "100, 100;w=60"),
+            ("ratelimit-remaining", "7"),
+            ("ratelimit-reset", "10"),
+        ]))
+        .unwrap();
+        assert_eq!(status.limit, Some(100));
+        assert_eq!(status.remaining, 7);
+
+        let status = RateLimitStatus::from_headers(&headers(&[
+            ("ratelimit", "\"default\";r=5;t=20"),
+            ("ratelimit-policy", "\"default\";q=50;w=60"),
+        ]))
+        .unwrap();
+        assert_eq!(status.limit, Some(50));
+        assert_eq!(status.remaining, 5);
+        assert!(status.reset_at.is_some());
+
+        let status =
+            RateLimitStatus::from_headers(&headers(&[("ratelimit", "limit=10, remaining=3, reset=5")]))
+                .unwrap();
+        assert_eq!(status.limit, Some(10));
+        assert_eq!(status.remaining, 3);
+    }
+
+    #[test]
+    fn test_no_headers() {
+        assert!(RateLimitStatus::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+
+        let date = (Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let date = date.replace("+0000", "GMT");
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay > Duration::from_secs(85) && delay <= Duration::from_secs(90));
+
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_throttle_paces_when_budget_is_low() {
+        let throttle = AdaptiveThrottle::default();
+        assert_eq!(throttle.reserve(), None);
+
+        throttle.observe(&headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "50"),
+            ("x-ratelimit-reset", "60"),
+        ]));
+        assert_eq!(throttle.reserve(), None);
+
+        throttle.observe(&headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "3"),
+            ("x-ratelimit-reset", "60"),
+        ]));
+        let delay = throttle.reserve().unwrap();
+        assert!(delay > Duration::from_secs(10) && delay <= Duration::from_secs(15));
+        assert_eq!(throttle.status().unwrap().remaining, 2);
+
+        throttle.observe(&headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "60"),
+        ]));
+        assert!(throttle.reserve().unwrap() > Duration::from_secs(55));
+    }
+}
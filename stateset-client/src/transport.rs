@@ -0,0 +1,368 @@
+//! Pluggable HTTP transports
+//!
+//! A [`Transport`] sends one fully prepared request and returns the buffered
+//! response. Authentication, middleware, rate limiting and retries all run in
+//! [`Client`](crate::Client) on top of it, so swapping the transport changes
+//! only how bytes reach the server.
+//!
+//! [`ReqwestTransport`] is the default. [`MemoryTransport`] answers requests
+//! from a route table of closures without touching the network, which keeps
+//! tests of code built on the client deterministic and offline:
+//!
+//! ```
+//! use stateset_client::{transport::MemoryTransport, Client};
+//! use stateset_core::Config;
+//!
+//! # async fn run() -> stateset_core::Result<()> {
+//! let transport = MemoryTransport::new()
+//!     .json(http::Method::GET, "/api/v1/orders/{id}", 200, serde_json::json!({"id": "ord_1"}));
+//!
+//! let client = Client::with_transport(Config::with_base_url("https://api.stateset.io")?, transport)?;
+//! let order: serde_json::Value = client.get("/api/v1/orders/ord_1").await?;
+//! assert_eq!(order["id"], "ord_1");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::download::Download;
+use crate::response::HttpResponse;
+use bytes::Bytes;
+use futures::future::{self, BoxFuture};
+use futures::TryStreamExt;
+use reqwest::{header::HeaderMap, Client as ReqwestClient, Method, StatusCode};
+use stateset_core::{Config, Error, Result};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// Sends a single HTTP request and buffers the response
+pub trait Transport: Send + Sync {
+    /// Send the request
+    ///
+    /// Non-success status codes are returned as responses, not errors; only
+    /// failures to get a response at all should be reported as `Err`.
+    fn send(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse>>;
+
+    /// Send the request and stream the response body
+    ///
+    /// The default implementation buffers the body with [`Transport::send`];
+    /// transports that can stream should override it.
+    fn send_streaming(&self, request: reqwest::Request) -> BoxFuture<'_, Result<Download>> {
+        Box::pin(async move { self.send(request).await.map(Download::from) })
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse>> {
+        (**self).send(request)
+    }
+
+    fn send_streaming(&self, request: reqwest::Request) -> BoxFuture<'_, Result<Download>> {
+        (**self).send_streaming(request)
+    }
+}
+
+/// The default transport backed by `reqwest`
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    http: ReqwestClient,
+    timeout: Duration,
+}
+
+impl ReqwestTransport {
+    /// Create a new reqwest transport from a configuration
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self::from_client(crate::build_http_client(config)?, config.timeout))
+    }
+
+    /// Wrap an existing reqwest client
+    pub fn from_client(http: ReqwestClient, timeout: Duration) -> Self {
+        Self { http, timeout }
+    }
+}
+
+impl ReqwestTransport {
+    async fn execute(http: ReqwestClient, request: reqwest::Request, timeout: Duration) -> Result<HttpResponse> {
+        let method = request.method().clone();
+        let response = http
+            .execute(request)
+            .await
+            .map_err(|e| crate::transport_error(e, timeout))?;
+
+        HttpResponse::from_reqwest(method, response).await
+    }
+
+    async fn execute_streaming(
+        http: ReqwestClient,
+        request: reqwest::Request,
+        timeout: Duration,
+    ) -> Result<Download> {
+        let response = http
+            .execute(request)
+            .await
+            .map_err(|e| crate::transport_error(e, timeout))?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let url = response.url().clone();
+        let body = response
+            .bytes_stream()
+            .map_err(move |e| crate::transport_error(e, timeout));
+
+        // Bodies are read on the I/O reactor when the caller is not on Tokio
+        #[cfg(not(feature = "tokio"))]
+        let body = crate::runtime::io::forward(body);
+
+        Ok(Download::new(status, headers, url, body))
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse>> {
+        let timeout = request.timeout().copied().unwrap_or(self.timeout);
+        let response = Self::execute(self.http.clone(), request, timeout);
+
+        #[cfg(feature = "tokio")]
+        return Box::pin(response);
+
+        #[cfg(not(feature = "tokio"))]
+        Box::pin(async move { crate::runtime::io::run(response).await? })
+    }
+
+    fn send_streaming(&self, request: reqwest::Request) -> BoxFuture<'_, Result<Download>> {
+        let timeout = request.timeout().copied().unwrap_or(self.timeout);
+        let download = Self::execute_streaming(self.http.clone(), request, timeout);
+
+        #[cfg(feature = "tokio")]
+        return Box::pin(download);
+
+        #[cfg(not(feature = "tokio"))]
+        Box::pin(async move { crate::runtime::io::run(download).await? })
+    }
+}
+
+/// Handler answering requests in a [`MemoryTransport`]
+type Handler = Box<dyn Fn(&reqwest::Request) -> Result<http::Response<Bytes>> + Send + Sync>;
+
+struct Route {
+    method: Method,
+    segments: Vec<String>,
+    handler: Handler,
+}
+
+impl Route {
+    /// Match a path against the route, treating `{name}` segments as wildcards
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if self.method != *method {
+            return false;
+        }
+
+        let mut segments = path.trim_matches('/').split('/');
+        self.segments.iter().all(|expected| match segments.next() {
+            Some(actual) => is_placeholder(expected) || expected == actual,
+            None => false,
+        }) && segments.next().is_none()
+    }
+}
+
+fn is_placeholder(segment: &str) -> bool {
+    segment.starts_with('{') && segment.ends_with('}')
+}
+
+/// A request received by a [`MemoryTransport`]
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// HTTP method
+    pub method: Method,
+    /// Full request URL
+    pub url: Url,
+    /// Request headers
+    pub headers: HeaderMap,
+    /// Request body, if it was buffered
+    pub body: Option<Bytes>,
+}
+
+impl RecordedRequest {
+    /// Deserialize the request body as JSON
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(self.body.as_deref().unwrap_or_default())
+            .map_err(|e| Error::Serialization {
+                message: format!("Failed to parse request body: {}", e),
+            })
+    }
+}
+
+/// In-memory transport answering requests from a route table
+///
+/// Routes are matched in registration order on method and path; path
+/// segments written as `{name}` match any value. Requests without a matching
+/// route get a 404. Every request is recorded and can be inspected with
+/// [`MemoryTransport::requests`].
+#[derive(Default)]
+pub struct MemoryTransport {
+    routes: Vec<Route>,
+    fallback: Option<Handler>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl std::fmt::Debug for MemoryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryTransport")
+            .field("routes", &self.routes.len())
+            .field("requests", &self.lock().len())
+            .finish()
+    }
+}
+
+impl MemoryTransport {
+    /// Create an empty transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests matching `method` and `path` with a closure
+    pub fn route<F>(mut self, method: Method, path: &str, handler: F) -> Self
+    where
+        F: Fn(&reqwest::Request) -> Result<http::Response<Bytes>> + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            method,
+            segments: path.trim_matches('/').split('/').map(str::to_string).collect(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// Answer requests matching `method` and `path` with a fixed JSON body
+    pub fn json(self, method: Method, path: &str, status: u16, body: serde_json::Value) -> Self {
+        let body = Bytes::from(body.to_string());
+        self.route(method, path, move |_| {
+            http::Response::builder()
+                .status(status)
+                .header("content-type", "application/json")
+                .body(body.clone())
+                .map_err(|e| Error::InvalidRequest {
+                    message: format!("Invalid in-memory response: {}", e),
+                    parameter: None,
+                })
+        })
+    }
+
+    /// Answer requests that match no route with a closure
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&reqwest::Request) -> Result<http::Response<Bytes>> + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<RecordedRequest>> {
+        match self.requests.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn respond(&self, request: &reqwest::Request) -> Result<HttpResponse> {
+        let handler = self
+            .routes
+            .iter()
+            .find(|route| route.matches(request.method(), request.url().path()))
+            .map(|route| &route.handler)
+            .or(self.fallback.as_ref());
+
+        let Some(handler) = handler else {
+            let body = serde_json::json!({
+                "message": format!("No route for {} {}", request.method(), request.url().path()),
+            });
+            return Ok(HttpResponse::new(
+                request.method().clone(),
+                request.url().clone(),
+                StatusCode::NOT_FOUND,
+                HeaderMap::new(),
+                body.to_string(),
+            ));
+        };
+
+        let (parts, body) = handler(request)?.into_parts();
+        Ok(HttpResponse::new(
+            request.method().clone(),
+            request.url().clone(),
+            parts.status,
+            parts.headers,
+            body,
+        ))
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse>> {
+        self.lock().push(RecordedRequest {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(Bytes::copy_from_slice),
+        });
+
+        Box::pin(future::ready(self.respond(&request)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: Method, path: &str) -> reqwest::Request {
+        let url = Url::parse("https://api.stateset.io").unwrap().join(path).unwrap();
+        reqwest::Request::new(method, url)
+    }
+
+    #[tokio::test]
+    async fn test_route_matching() {
+        let transport = MemoryTransport::new()
+            .json(Method::GET, "/api/v1/orders", 200, serde_json::json!([]))
+            .json(Method::GET, "/api/v1/orders/{id}", 200, serde_json::json!({"id": "ord_1"}))
+            .json(Method::POST, "/api/v1/orders", 201, serde_json::json!({"id": "ord_2"}));
+
+        let response = transport.send(request(Method::GET, "/api/v1/orders/ord_1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.json::<serde_json::Value>().unwrap()["id"], "ord_1");
+
+        let response = transport.send(request(Method::POST, "/api/v1/orders")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = transport
+            .send(request(Method::GET, "/api/v1/orders/ord_1/items"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let recorded = transport.requests();
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(recorded[1].method, Method::POST);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_handler() {
+        let transport = MemoryTransport::new().fallback(|request| {
+            Ok(http::Response::builder()
+                .status(503)
+                .header("x-path", request.url().path())
+                .body(Bytes::new())
+                .unwrap())
+        });
+
+        let response = transport.send(request(Method::GET, "/api/v1/products")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.header("x-path"), Some("/api/v1/products"));
+    }
+}
@@ -0,0 +1,397 @@
+//! Multipart file uploads
+//!
+//! An [`UploadFile`] describes one file part: where its bytes come from, its
+//! file name and content type. Content types are guessed from the file
+//! extension, falling back to sniffing well-known signatures for in-memory
+//! data. Bodies are streamed in chunks and never buffered as a whole.
+//!
+//! Forms are rebuilt for every attempt, so uploads from paths and bytes are
+//! retried like any other request. Uploads from a reader can only be sent
+//! once.
+
+use bytes::Bytes;
+use futures::io::{AsyncRead, AsyncReadExt};
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::multipart::{Form, Part};
+use stateset_core::{Error, Result};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Size of the chunks read from files and readers
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default form field name for file parts
+pub const DEFAULT_FIELD_NAME: &str = "file";
+
+type BoxReader = Pin<Box<dyn AsyncRead + Send>>;
+type ProgressFn = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// Progress of an upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Bytes handed to the transport so far
+    pub uploaded: u64,
+    /// Total size of the file, if known
+    pub total: Option<u64>,
+}
+
+enum Source {
+    Path(PathBuf),
+    Bytes(Bytes),
+    Reader(Arc<Mutex<Option<BoxReader>>>),
+}
+
+/// A file to upload as one part of a multipart form
+pub struct UploadFile {
+    field_name: String,
+    filename: String,
+    content_type: Option<String>,
+    length: Option<u64>,
+    source: Source,
+    progress: Option<ProgressFn>,
+}
+
+impl std::fmt::Debug for UploadFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadFile")
+            .field("field_name", &self.field_name)
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type())
+            .field("length", &self.length)
+            .finish()
+    }
+}
+
+impl UploadFile {
+    /// Upload a file from disk, streaming it in chunks
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let metadata = std::fs::metadata(path).map_err(|e| Error::InvalidRequest {
+            message: format!("Cannot read upload '{}': {}", path.display(), e),
+            parameter: Some("path".to_string()),
+        })?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| DEFAULT_FIELD_NAME.to_string());
+
+        Ok(Self::new(filename, Some(metadata.len()), Source::Path(path.to_path_buf())))
+    }
+
+    /// Upload data held in memory
+    pub fn from_bytes(filename: impl Into<String>, data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        Self::new(filename.into(), Some(data.len() as u64), Source::Bytes(data))
+    }
+
+    /// Upload from an async reader
+    ///
+    /// Readers can only be consumed once, so failed uploads from a reader are
+    /// not retried. Pass `length` when known to report progress totals.
+    pub fn from_reader(
+        filename: impl Into<String>,
+        reader: impl AsyncRead + Send + 'static,
+        length: Option<u64>,
+    ) -> Self {
+        let reader: BoxReader = Box::pin(reader);
+        Self::new(filename.into(), length, Source::Reader(Arc::new(Mutex::new(Some(reader)))))
+    }
+
+    fn new(filename: String, length: Option<u64>, source: Source) -> Self {
+        Self {
+            field_name: DEFAULT_FIELD_NAME.to_string(),
+            filename,
+            content_type: None,
+            length,
+            source,
+            progress: None,
+        }
+    }
+
+    /// Set the form field name (defaults to `file`)
+    pub fn field_name(mut self, name: impl Into<String>) -> Self {
+        self.field_name = name.into();
+        self
+    }
+
+    /// Set the file name sent to the server
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = filename.into();
+        self
+    }
+
+    /// Override the detected content type
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Report progress as chunks are handed to the transport
+    pub fn on_progress(mut self, progress: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Content type that will be sent, explicit or detected
+    pub fn content_type(&self) -> String {
+        if let Some(content_type) = &self.content_type {
+            return content_type.clone();
+        }
+
+        let sniffed = match &self.source {
+            Source::Bytes(data) => sniff(data),
+            _ => None,
+        };
+        mime_guess::from_path(&self.filename)
+            .first_raw()
+            .or(sniffed)
+            .unwrap_or("application/octet-stream")
+            .to_string()
+    }
+
+    /// Build a multipart part for one attempt
+    fn part(&self) -> Result<Part> {
+        let chunks: Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>> = match &self.source {
+            Source::Bytes(data) => {
+                let data = data.clone();
+                Box::pin(stream::iter(
+                    (0..data.len())
+                        .step_by(CHUNK_SIZE)
+                        .map(move |start| Ok(data.slice(start..(start + CHUNK_SIZE).min(data.len())))),
+                ))
+            }
+            Source::Path(path) => {
+                let file = std::fs::File::open(path).map_err(|e| Error::InvalidRequest {
+                    message: format!("Cannot open upload '{}': {}", path.display(), e),
+                    parameter: Some("path".to_string()),
+                })?;
+                read_file_chunks(tokio::fs::File::from_std(file))
+            }
+            Source::Reader(reader) => {
+                let reader = reader
+                    .lock()
+                    .map_err(|_| Error::Other("Upload reader lock poisoned".to_string()))?
+                    .take()
+                    .ok_or_else(|| Error::InvalidRequest {
+                        message: format!("Upload of '{}' from a reader cannot be sent twice", self.filename),
+                        parameter: Some(self.field_name.clone()),
+                    })?;
+                read_chunks(reader)
+            }
+        };
+
+        let chunks = match &self.progress {
+            Some(progress) => track_progress(chunks, progress.clone(), self.length),
+            None => chunks,
+        };
+
+        let body = reqwest::Body::wrap_stream(chunks);
+        let part = match self.length {
+            Some(length) => Part::stream_with_length(body, length),
+            None => Part::stream(body),
+        };
+
+        part.file_name(self.filename.clone())
+            .mime_str(&self.content_type())
+            .map_err(|e| Error::InvalidRequest {
+                message: format!("Invalid content type for '{}': {}", self.filename, e),
+                parameter: Some(self.field_name.clone()),
+            })
+    }
+}
+
+/// A multipart form with file parts and text fields
+#[derive(Debug, Default)]
+pub struct MultipartUpload {
+    files: Vec<UploadFile>,
+    fields: Vec<(String, String)>,
+}
+
+impl MultipartUpload {
+    /// Create an empty form
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file part
+    pub fn file(mut self, file: UploadFile) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    /// Add a text field
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add a text field if a value is present
+    pub fn text_opt(self, name: impl Into<String>, value: Option<impl Into<String>>) -> Self {
+        match value {
+            Some(value) => self.text(name, value),
+            None => self,
+        }
+    }
+
+    /// Whether the form can only be sent once
+    pub(crate) fn is_single_use(&self) -> bool {
+        self.files.iter().any(|file| matches!(file.source, Source::Reader(_)))
+    }
+
+    /// Build a fresh form for one attempt
+    pub(crate) fn form(&self) -> Result<Form> {
+        let mut form = Form::new();
+        for (name, value) in &self.fields {
+            form = form.text(name.clone(), value.clone());
+        }
+        for file in &self.files {
+            form = form.part(file.field_name.clone(), file.part()?);
+        }
+        Ok(form)
+    }
+}
+
+impl From<UploadFile> for MultipartUpload {
+    fn from(file: UploadFile) -> Self {
+        Self::new().file(file)
+    }
+}
+
+/// Read an async reader as a stream of chunks
+fn read_chunks(reader: BoxReader) -> Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>> {
+    Box::pin(stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        match reader.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buffer.truncate(n);
+                Some((Ok(Bytes::from(buffer)), Some(reader)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    }))
+}
+
+/// Read a file as a stream of chunks
+///
+/// Reads run on Tokio's blocking pool rather than the thread polling the
+/// body, which is always inside the Tokio context reqwest sends from.
+fn read_file_chunks(file: tokio::fs::File) -> Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>> {
+    use tokio::io::AsyncReadExt as _;
+
+    Box::pin(stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buffer.truncate(n);
+                Some((Ok(Bytes::from(buffer)), Some(file)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    }))
+}
+
+/// Report cumulative progress as chunks pass through
+fn track_progress(
+    chunks: Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>,
+    progress: ProgressFn,
+    total: Option<u64>,
+) -> Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>> {
+    let mut uploaded = 0;
+    Box::pin(chunks.inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            uploaded += chunk.len() as u64;
+            progress(UploadProgress { uploaded, total });
+        }
+    }))
+}
+
+/// Detect common file types from their leading bytes
+fn sniff(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+    ];
+
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return Some("video/mp4");
+    }
+
+    SIGNATURES
+        .iter()
+        .find(|(signature, _)| data.starts_with(signature))
+        .map(|(_, content_type)| *content_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_detection() {
+        assert_eq!(UploadFile::from_bytes("photo.jpg", "x").content_type(), "image/jpeg");
+        assert_eq!(UploadFile::from_bytes("manual.pdf", "x").content_type(), "application/pdf");
+        assert_eq!(
+            UploadFile::from_bytes("upload", &b"\x89PNG\r\n\x1a\n...."[..]).content_type(),
+            "image/png"
+        );
+        assert_eq!(
+            UploadFile::from_bytes("upload", "plain").content_type(),
+            "application/octet-stream"
+        );
+        assert_eq!(
+            UploadFile::from_bytes("data.bin", "x")
+                .with_content_type("text/csv")
+                .content_type(),
+            "text/csv"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reader_chunks_and_progress() {
+        let data = vec![7u8; CHUNK_SIZE + 10];
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+
+        let chunks = track_progress(
+            read_chunks(Box::pin(futures::io::Cursor::new(data))),
+            Arc::new(move |p| recorder.lock().unwrap().push(p)),
+            Some(CHUNK_SIZE as u64 + 10),
+        );
+        let chunks: Vec<_> = chunks.collect().await;
+
+        assert_eq!(chunks.len(), 2);
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.last().unwrap().uploaded, CHUNK_SIZE as u64 + 10);
+    }
+
+    #[tokio::test]
+    async fn test_file_chunks() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, &vec![1u8; CHUNK_SIZE * 2 + 1]).unwrap();
+
+        let std_file = std::fs::File::open(file.path()).unwrap();
+        let chunks: Vec<_> = read_file_chunks(tokio::fs::File::from_std(std_file)).collect().await;
+        let lengths: Vec<_> = chunks.into_iter().map(|chunk| chunk.unwrap().len()).collect();
+        assert!(lengths.iter().all(|length| (1..=CHUNK_SIZE).contains(length)));
+        assert_eq!(lengths.iter().sum::<usize>(), CHUNK_SIZE * 2 + 1);
+    }
+
+    #[test]
+    fn test_reader_upload_is_single_use() {
+        let file = UploadFile::from_reader("notes.txt", futures::io::Cursor::new(b"notes".to_vec()), Some(5));
+        assert!(file.part().is_ok());
+        assert!(file.part().is_err());
+    }
+}
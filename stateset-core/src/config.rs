@@ -1,13 +1,33 @@
 //! Configuration types for StateSet SDK
 
+use std::path::Path;
 use std::time::Duration;
 use url::Url;
 
+mod load;
+
 /// Configuration for the StateSet client
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Base URL for the StateSet API
     pub base_url: Url,
+    /// Endpoints tried in order when `base_url` is unhealthy
+    pub failover_endpoints: Vec<Endpoint>,
+    /// When endpoints are taken out of rotation and brought back
+    pub failover: FailoverSettings,
+    /// Per-host, per-resource circuit breakers (disabled if `None`)
+    pub circuit_breaker: Option<CircuitBreakerSettings>,
+    /// API version segment of resource paths, e.g. `"v1"` in `/api/v1/orders`
+    pub api_version: String,
+    /// Versions pinned for individual resources, keyed by resource name
+    /// (e.g. `"orders"`), overriding `api_version`
+    pub api_versions: std::collections::HashMap<String, String>,
+    /// Path in front of `/api/<version>`, e.g. `/stateset` for a gateway
+    /// that serves the API under `/stateset/api/v1/...`
+    pub api_prefix: Option<String>,
+    /// Header that carries the API version of each resource request, e.g.
+    /// `"StateSet-Version"`
+    pub api_version_header: Option<String>,
     /// Request timeout
     pub timeout: Duration,
     /// Connection timeout
@@ -20,14 +40,25 @@ pub struct Config {
     pub max_retry_delay: Duration,
     /// Retry multiplier for exponential backoff
     pub retry_multiplier: f64,
-    /// Rate limit per minute (if enabled)
+    /// Client-side rate limit as (requests, per duration), shared by all endpoints
     pub rate_limit: Option<(u32, Duration)>,
+    /// Additional per-endpoint-group rate limits, keyed by resource name
+    /// (e.g. `"analytics"`, `"orders"`)
+    pub rate_limit_groups: std::collections::HashMap<String, (u32, Duration)>,
     /// User agent string
     pub user_agent: String,
     /// Connection pool settings
     pub pool_settings: PoolSettings,
-    /// Request compression
+    /// Maximum background-priority requests in flight; defaults to half of
+    /// `pool_settings.max_total_connections`
+    pub background_concurrency: Option<usize>,
+    /// Compression: negotiates gzip/brotli/zstd response decoding and
+    /// allows request body compression
     pub compression: bool,
+    /// Gzip request bodies of at least this many bytes (disabled if `None`)
+    pub request_compression_threshold: Option<usize>,
+    /// Endpoint groups whose request bodies are never compressed
+    pub request_compression_exempt: std::collections::HashSet<String>,
     /// Keep-alive settings
     pub keep_alive: Option<Duration>,
     /// Maximum redirects to follow
@@ -36,6 +67,31 @@ pub struct Config {
     pub default_headers: std::collections::HashMap<String, String>,
     /// TLS verification (should only be disabled for testing)
     pub tls_verification: bool,
+    /// TLS implementation; defaults to rustls if compiled in, else native-tls
+    pub tls_backend: Option<TlsBackend>,
+    /// Additional trusted root certificates, e.g. a private CA
+    pub root_certificates: Vec<Certificate>,
+    /// Trust the TLS backend's built-in root certificates
+    pub built_in_root_certificates: bool,
+    /// Client certificate presented to mutual-TLS gateways
+    pub client_identity: Option<ClientIdentity>,
+    /// Accepted public keys as `sha256/<base64>` hashes of the
+    /// SubjectPublicKeyInfo; a connection succeeds if any certificate in the
+    /// server's chain matches
+    pub certificate_pins: Vec<String>,
+    /// Proxy for all requests
+    pub proxy: Option<ProxySettings>,
+    /// Honour `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` when no proxy is set
+    pub system_proxy: bool,
+    /// Automatically attach an `Idempotency-Key` to POST and PATCH requests
+    pub idempotency_keys: bool,
+    /// Pace requests using the rate-limit headers returned by the server
+    pub adaptive_throttling: bool,
+    /// Send W3C `traceparent`/`tracestate` headers with every request;
+    /// off by default
+    pub propagate_trace_context: bool,
+    /// Credentials the client authenticates with on creation
+    pub credentials: Option<CredentialSettings>,
 }
 
 /// Connection pool settings
@@ -43,7 +99,7 @@ pub struct Config {
 pub struct PoolSettings {
     /// Maximum number of connections per host
     pub max_connections_per_host: usize,
-    /// Maximum total number of connections
+    /// Maximum number of requests in flight across all clones of a client
     pub max_total_connections: usize,
     /// Connection idle timeout
     pub idle_timeout: Duration,
@@ -62,6 +118,218 @@ impl Default for PoolSettings {
     }
 }
 
+/// A named alternative base URL, e.g. another region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// Name reported in response metadata, e.g. `"eu-west"`
+    pub name: String,
+    /// Base URL of the endpoint
+    pub url: Url,
+}
+
+/// Failover thresholds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailoverSettings {
+    /// Consecutive server errors or timeouts that take an endpoint out of
+    /// rotation; failed connections take it out immediately
+    pub failure_threshold: u32,
+    /// How long an endpoint stays out of rotation before it is tried again
+    pub cool_down: Duration,
+}
+
+impl Default for FailoverSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Circuit breaker thresholds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerSettings {
+    /// Consecutive server errors, timeouts or network errors that open a
+    /// breaker
+    pub failure_threshold: u32,
+    /// How long an open breaker fails requests fast before probing
+    pub recovery_timeout: Duration,
+    /// Probes let through at once while half-open; this many must succeed to
+    /// close the breaker
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            recovery_timeout: Duration::from_secs(30),
+            half_open_probes: 1,
+        }
+    }
+}
+
+/// Name of the endpoint at [`Config::base_url`]
+pub const PRIMARY_ENDPOINT: &str = "primary";
+
+/// API version used when none is configured
+pub const DEFAULT_API_VERSION: &str = "v1";
+
+/// TLS implementation used for HTTPS connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TlsBackend {
+    /// rustls, enabled by the `rustls` feature
+    Rustls,
+    /// The platform TLS library, enabled by the `native-tls` feature
+    NativeTls,
+}
+
+/// A trusted root certificate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Certificate {
+    /// One or more PEM-encoded certificates
+    Pem(Vec<u8>),
+    /// A single DER-encoded certificate
+    Der(Vec<u8>),
+}
+
+impl Certificate {
+    /// Read a PEM bundle from a file
+    pub fn from_pem_file(path: impl AsRef<Path>) -> crate::Result<Self> {
+        read_file(path.as_ref()).map(Self::Pem)
+    }
+
+    /// Read a DER certificate from a file
+    pub fn from_der_file(path: impl AsRef<Path>) -> crate::Result<Self> {
+        read_file(path.as_ref()).map(Self::Der)
+    }
+}
+
+/// A client certificate and private key for mutual TLS
+#[derive(Clone, PartialEq, Eq)]
+pub enum ClientIdentity {
+    /// PEM certificate chain, leaf first, and a PEM private key
+    ///
+    /// native-tls only accepts PKCS#8 keys (`BEGIN PRIVATE KEY`); rustls also
+    /// accepts PKCS#1 and SEC1 keys.
+    Pem {
+        /// Certificate chain
+        certificate_chain: Vec<u8>,
+        /// Private key
+        private_key: Vec<u8>,
+    },
+    /// A PKCS#12 archive, supported by the native-tls backend only
+    Pkcs12 {
+        /// DER-encoded archive
+        der: Vec<u8>,
+        /// Archive password
+        password: String,
+    },
+}
+
+impl ClientIdentity {
+    /// Read a PEM certificate chain and private key from files
+    pub fn from_pem_files(
+        certificate_chain: impl AsRef<Path>,
+        private_key: impl AsRef<Path>,
+    ) -> crate::Result<Self> {
+        Ok(Self::Pem {
+            certificate_chain: read_file(certificate_chain.as_ref())?,
+            private_key: read_file(private_key.as_ref())?,
+        })
+    }
+
+    /// Read a PKCS#12 archive from a file
+    pub fn from_pkcs12_file(path: impl AsRef<Path>, password: impl Into<String>) -> crate::Result<Self> {
+        Ok(Self::Pkcs12 {
+            der: read_file(path.as_ref())?,
+            password: password.into(),
+        })
+    }
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pem { certificate_chain, .. } => f
+                .debug_struct("Pem")
+                .field("certificate_chain", &format_args!("{} bytes", certificate_chain.len()))
+                .field("private_key", &"[REDACTED]")
+                .finish(),
+            Self::Pkcs12 { der, .. } => f
+                .debug_struct("Pkcs12")
+                .field("der", &format_args!("{} bytes", der.len()))
+                .field("password", &"[REDACTED]")
+                .finish(),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> crate::Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| {
+        crate::Error::config_with_hint(
+            format!("Failed to read {}: {}", path.display(), e),
+            "Check the file path and permissions",
+        )
+    })
+}
+
+/// Credentials loaded together with the configuration
+#[derive(Clone, PartialEq, Eq)]
+pub enum CredentialSettings {
+    /// Bearer token
+    Bearer(String),
+    /// API key
+    ApiKey(String),
+    /// OAuth2 client credentials
+    OAuth2 {
+        /// Client ID
+        client_id: String,
+        /// Client secret
+        client_secret: String,
+    },
+}
+
+impl std::fmt::Debug for CredentialSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bearer(_) => f.write_str("Bearer([REDACTED])"),
+            Self::ApiKey(_) => f.write_str("ApiKey([REDACTED])"),
+            Self::OAuth2 { client_id, .. } => f
+                .debug_struct("OAuth2")
+                .field("client_id", client_id)
+                .field("client_secret", &"[REDACTED]")
+                .finish(),
+        }
+    }
+}
+
+/// Proxy settings
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxySettings {
+    /// Proxy URL with an `http`, `https`, `socks5` or `socks5h` scheme
+    pub url: Url,
+    /// Username and password for the proxy
+    pub credentials: Option<(String, String)>,
+    /// Hosts reached directly, in `NO_PROXY` syntax: host names, `.domain`
+    /// suffixes, IP addresses and CIDR blocks
+    pub no_proxy: Vec<String>,
+}
+
+impl std::fmt::Debug for ProxySettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut url = self.url.clone();
+        if url.password().is_some() {
+            let _ = url.set_password(Some("REDACTED"));
+        }
+        f.debug_struct("ProxySettings")
+            .field("url", &url.as_str())
+            .field("credentials", &self.credentials.as_ref().map(|(username, _)| (username, "[REDACTED]")))
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut default_headers = std::collections::HashMap::new();
@@ -69,13 +337,16 @@ impl Default for Config {
             "Accept".to_string(),
             "application/json".to_string(),
         );
-        default_headers.insert(
-            "Accept-Encoding".to_string(),
-            "gzip, deflate, br".to_string(),
-        );
 
         Self {
             base_url: Url::parse("https://api.stateset.io").unwrap(),
+            failover_endpoints: Vec::new(),
+            failover: FailoverSettings::default(),
+            circuit_breaker: None,
+            api_version: DEFAULT_API_VERSION.to_string(),
+            api_versions: std::collections::HashMap::new(),
+            api_prefix: None,
+            api_version_header: None,
             timeout: Duration::from_secs(30),
             connect_timeout: Duration::from_secs(10),
             retry_attempts: 3,
@@ -83,13 +354,28 @@ impl Default for Config {
             max_retry_delay: Duration::from_secs(60),
             retry_multiplier: 2.0,
             rate_limit: None,
+            rate_limit_groups: std::collections::HashMap::new(),
             user_agent: format!("stateset-rust-sdk/{}", env!("CARGO_PKG_VERSION")),
             pool_settings: PoolSettings::default(),
+            background_concurrency: None,
             compression: true,
+            request_compression_threshold: None,
+            request_compression_exempt: std::collections::HashSet::new(),
             keep_alive: Some(Duration::from_secs(90)),
             max_redirects: 10,
             default_headers,
             tls_verification: true,
+            tls_backend: None,
+            root_certificates: Vec::new(),
+            built_in_root_certificates: true,
+            client_identity: None,
+            certificate_pins: Vec::new(),
+            proxy: None,
+            system_proxy: true,
+            idempotency_keys: true,
+            adaptive_throttling: true,
+            propagate_trace_context: false,
+            credentials: None,
         }
     }
 }
@@ -124,6 +410,39 @@ impl Config {
             ));
         }
 
+        // Validate failover endpoints
+        let mut names = std::collections::HashSet::from([PRIMARY_ENDPOINT]);
+        for endpoint in &self.failover_endpoints {
+            if !matches!(endpoint.url.scheme(), "http" | "https") {
+                return Err(crate::Error::config_with_hint(
+                    format!("Invalid URL scheme for endpoint {}", endpoint.name),
+                    "Only HTTP and HTTPS are supported",
+                ));
+            }
+            if !names.insert(&endpoint.name) {
+                return Err(crate::Error::config_with_hint(
+                    format!("Duplicate endpoint name: {}", endpoint.name),
+                    format!("Give every endpoint a unique name other than {:?}", PRIMARY_ENDPOINT),
+                ));
+            }
+        }
+
+        if self.failover.failure_threshold == 0 || self.failover.cool_down.is_zero() {
+            return Err(crate::Error::config_with_hint(
+                "Failover threshold and cool-down must be non-zero",
+                "Use values like 3 failures and Duration::from_secs(30)",
+            ));
+        }
+
+        if let Some(breaker) = &self.circuit_breaker {
+            if breaker.failure_threshold == 0 || breaker.recovery_timeout.is_zero() || breaker.half_open_probes == 0 {
+                return Err(crate::Error::config_with_hint(
+                    "Circuit breaker threshold, recovery timeout and half-open probes must be non-zero",
+                    "Use values like 5 failures, Duration::from_secs(30) and 1 probe",
+                ));
+            }
+        }
+
         // Validate timeouts
         if self.timeout.as_millis() == 0 {
             return Err(crate::Error::config_with_hint(
@@ -147,6 +466,49 @@ impl Config {
             ));
         }
 
+        // Validate API paths
+        let versions = std::iter::once(&self.api_version).chain(self.api_versions.values());
+        for version in versions {
+            if !is_path_segment(version) {
+                return Err(crate::Error::config_with_hint(
+                    format!("Invalid API version: {:?}", version),
+                    "Use a single path segment such as \"v2\"",
+                ));
+            }
+        }
+        if let Some(prefix) = &self.api_prefix {
+            let valid = prefix.starts_with('/')
+                && !prefix.ends_with('/')
+                && prefix[1..].split('/').all(is_path_segment);
+            if !valid {
+                return Err(crate::Error::config_with_hint(
+                    format!("Invalid API prefix: {:?}", prefix),
+                    "Use a path such as \"/gateway/stateset\"",
+                ));
+            }
+        }
+        if let Some(header) = &self.api_version_header {
+            let valid = !header.is_empty()
+                && header.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+            if !valid {
+                return Err(crate::Error::config_with_hint(
+                    format!("Invalid API version header name: {:?}", header),
+                    "Use a header name such as \"StateSet-Version\"",
+                ));
+            }
+        }
+
+        // Validate rate limits
+        let rate_limits = self.rate_limit.iter().chain(self.rate_limit_groups.values());
+        for (requests, per) in rate_limits {
+            if *requests == 0 || per.is_zero() {
+                return Err(crate::Error::config_with_hint(
+                    "Rate limit must allow at least one request per non-zero duration",
+                    "Use a value like rate_limit(100, Duration::from_secs(60))",
+                ));
+            }
+        }
+
         // Validate pool settings
         if self.pool_settings.max_connections_per_host == 0 {
             return Err(crate::Error::config_with_hint(
@@ -155,9 +517,62 @@ impl Config {
             ));
         }
 
+        if self.pool_settings.max_total_connections == 0 {
+            return Err(crate::Error::config_with_hint(
+                "Max total connections cannot be zero",
+                "Set a reasonable value like 100",
+            ));
+        }
+
+        // Validate network settings
+        if let Some(proxy) = &self.proxy {
+            if !matches!(proxy.url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+                return Err(crate::Error::config_with_hint(
+                    format!("Unsupported proxy scheme: {}", proxy.url.scheme()),
+                    "Use an http://, https://, socks5:// or socks5h:// proxy URL",
+                ));
+            }
+        }
+
+        if let Some(pin) = self.certificate_pins.iter().find(|pin| !is_valid_pin(pin)) {
+            return Err(crate::Error::config_with_hint(
+                format!("Invalid certificate pin: {}", pin),
+                "Pins are sha256/ followed by the base64 SHA-256 of the SubjectPublicKeyInfo",
+            ));
+        }
+
+        if !self.built_in_root_certificates
+            && self.root_certificates.is_empty()
+            && self.tls_verification
+        {
+            return Err(crate::Error::config_with_hint(
+                "No trusted root certificates",
+                "Add a root certificate or keep the built-in root certificates",
+            ));
+        }
+
+        if let Some(background) = self.background_concurrency {
+            if background == 0 || background > self.pool_settings.max_total_connections {
+                return Err(crate::Error::config_with_hint(
+                    "Background concurrency must be between 1 and the max total connections",
+                    "Leave room for interactive requests, e.g. half of max_total_connections",
+                ));
+            }
+        }
+
         Ok(())
     }
 
+    /// Whether any TLS setting differs from the backend defaults
+    pub fn has_custom_tls(&self) -> bool {
+        !self.tls_verification
+            || self.tls_backend.is_some()
+            || !self.root_certificates.is_empty()
+            || !self.built_in_root_certificates
+            || self.client_identity.is_some()
+            || !self.certificate_pins.is_empty()
+    }
+
     /// Get the effective timeout for retries
     pub fn total_timeout(&self) -> Duration {
         let mut total = Duration::from_millis(0);
@@ -175,10 +590,37 @@ impl Config {
     }
 }
 
+/// Check that `segment` is a non-empty path segment needing no escaping
+fn is_path_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-._~".contains(&b))
+}
+
+/// Check the `sha256/<base64>` pin format
+fn is_valid_pin(pin: &str) -> bool {
+    // 32 bytes encode to 43 base64 characters plus one `=` of padding
+    pin.strip_prefix("sha256/").is_some_and(|hash| {
+        hash.len() == 44
+            && hash.ends_with('=')
+            && hash[..43]
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+    })
+}
+
 /// Builder for creating Config instances
 #[derive(Debug, Default)]
 pub struct ConfigBuilder {
     base_url: Option<Url>,
+    failover_endpoints: Vec<(String, String)>,
+    failover: Option<FailoverSettings>,
+    circuit_breaker: Option<CircuitBreakerSettings>,
+    api_version: Option<String>,
+    api_versions: std::collections::HashMap<String, String>,
+    api_prefix: Option<String>,
+    api_version_header: Option<String>,
     timeout: Option<Duration>,
     connect_timeout: Option<Duration>,
     retry_attempts: Option<u32>,
@@ -186,13 +628,30 @@ pub struct ConfigBuilder {
     max_retry_delay: Option<Duration>,
     retry_multiplier: Option<f64>,
     rate_limit: Option<(u32, Duration)>,
+    rate_limit_groups: Option<std::collections::HashMap<String, (u32, Duration)>>,
     user_agent: Option<String>,
     pool_settings: Option<PoolSettings>,
+    background_concurrency: Option<usize>,
     compression: Option<bool>,
+    request_compression_threshold: Option<usize>,
+    request_compression_exempt: Option<std::collections::HashSet<String>>,
     keep_alive: Option<Duration>,
     max_redirects: Option<u32>,
     default_headers: Option<std::collections::HashMap<String, String>>,
     tls_verification: Option<bool>,
+    tls_backend: Option<TlsBackend>,
+    root_certificates: Vec<Certificate>,
+    built_in_root_certificates: Option<bool>,
+    client_identity: Option<ClientIdentity>,
+    certificate_pins: Vec<String>,
+    proxy_url: Option<String>,
+    proxy_credentials: Option<(String, String)>,
+    no_proxy: Vec<String>,
+    system_proxy: Option<bool>,
+    idempotency_keys: Option<bool>,
+    adaptive_throttling: Option<bool>,
+    propagate_trace_context: Option<bool>,
+    credentials: Option<CredentialSettings>,
 }
 
 impl ConfigBuilder {
@@ -202,6 +661,64 @@ impl ConfigBuilder {
         self
     }
 
+    /// Add a failover endpoint, tried in the order added
+    ///
+    /// Requests go to the first healthy endpoint, starting with the base URL,
+    /// and return to it once its cool-down has passed. A path in `url`, such
+    /// as `https://gw.example.com/eu`, is prepended to request paths.
+    pub fn failover_endpoint(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.failover_endpoints.push((name.into(), url.into()));
+        self
+    }
+
+    /// Set when endpoints are taken out of rotation and brought back
+    pub fn failover(mut self, settings: FailoverSettings) -> Self {
+        self.failover = Some(settings);
+        self
+    }
+
+    /// Enable circuit breakers, one per host and resource group
+    ///
+    /// A breaker opens after `failure_threshold` consecutive failures and
+    /// fails requests fast until the recovery timeout has passed, then lets
+    /// `half_open_probes` requests through to decide whether to close.
+    pub fn circuit_breaker(mut self, settings: CircuitBreakerSettings) -> Self {
+        self.circuit_breaker = Some(settings);
+        self
+    }
+
+    /// Set the API version used in resource paths, e.g. `"v2"`
+    pub fn api_version(mut self, version: impl Into<String>) -> Self {
+        self.api_version = Some(version.into());
+        self
+    }
+
+    /// Pin one resource to an API version, e.g. while migrating it to `v2`
+    ///
+    /// Resources are named after their path segment, such as `"orders"` or
+    /// `"work-orders"`. Nested resources can be pinned too: a pin for
+    /// `"shipments"` applies to `/orders/{id}/shipments` as well, ahead of
+    /// one for `"orders"`.
+    pub fn resource_api_version(mut self, resource: impl Into<String>, version: impl Into<String>) -> Self {
+        self.api_versions.insert(resource.into(), version.into());
+        self
+    }
+
+    /// Serve resource paths under a prefix, e.g. `"/stateset"` for
+    /// `/stateset/api/v1/orders`
+    pub fn api_prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        let prefix = prefix.as_ref().trim_matches('/');
+        self.api_prefix = (!prefix.is_empty()).then(|| format!("/{}", prefix));
+        self
+    }
+
+    /// Send the API version of each resource request in a header, e.g.
+    /// `"StateSet-Version"`
+    pub fn api_version_header(mut self, name: impl Into<String>) -> Self {
+        self.api_version_header = Some(name.into());
+        self
+    }
+
     /// Set the request timeout
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
@@ -244,6 +761,18 @@ impl ConfigBuilder {
         self
     }
 
+    /// Set a rate limit for one endpoint group (requests per duration)
+    ///
+    /// Groups are named after the resource segment of the path, so
+    /// `rate_limit_group("analytics", 10, Duration::from_secs(60))` applies to
+    /// every `/api/v1/analytics/...` request on top of the global limit.
+    pub fn rate_limit_group(mut self, group: impl Into<String>, requests: u32, per: Duration) -> Self {
+        self.rate_limit_groups
+            .get_or_insert_with(std::collections::HashMap::new)
+            .insert(group.into(), (requests, per));
+        self
+    }
+
     /// Set a custom user agent
     pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
         self.user_agent = Some(user_agent.into());
@@ -256,12 +785,44 @@ impl ConfigBuilder {
         self
     }
 
+    /// Limit background-priority requests in flight
+    ///
+    /// Defaults to half of `max_total_connections`, leaving the rest for
+    /// interactive requests.
+    pub fn background_concurrency(mut self, limit: usize) -> Self {
+        self.background_concurrency = Some(limit);
+        self
+    }
+
     /// Enable or disable compression
+    ///
+    /// `Accept-Encoding` is derived from this setting; when disabled, no
+    /// compressed responses are requested.
     pub fn compression(mut self, enabled: bool) -> Self {
         self.compression = Some(enabled);
         self
     }
 
+    /// Gzip request bodies of at least `bytes` bytes
+    ///
+    /// Only takes effect while compression is enabled. Useful for batch
+    /// creates and bulk inventory adjustments.
+    pub fn request_compression_threshold(mut self, bytes: usize) -> Self {
+        self.request_compression_threshold = Some(bytes);
+        self
+    }
+
+    /// Never compress request bodies sent to an endpoint group
+    ///
+    /// `request_compression_exempt("inventory")` applies to every
+    /// `/api/v1/inventory/...` request.
+    pub fn request_compression_exempt(mut self, group: impl Into<String>) -> Self {
+        self.request_compression_exempt
+            .get_or_insert_with(std::collections::HashSet::new)
+            .insert(group.into());
+        self
+    }
+
     /// Set keep-alive timeout
     pub fn keep_alive(mut self, timeout: Option<Duration>) -> Self {
         self.keep_alive = timeout;
@@ -288,6 +849,107 @@ impl ConfigBuilder {
         self
     }
 
+    /// Select the TLS backend
+    ///
+    /// The backend must be enabled with the `rustls` or `native-tls` feature.
+    pub fn tls_backend(mut self, backend: TlsBackend) -> Self {
+        self.tls_backend = Some(backend);
+        self
+    }
+
+    /// Trust an additional root certificate, e.g. a private CA
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Trust the TLS backend's built-in root certificates (default: true)
+    ///
+    /// Disable to trust only the certificates added with
+    /// [`add_root_certificate`](Self::add_root_certificate).
+    pub fn built_in_root_certificates(mut self, enabled: bool) -> Self {
+        self.built_in_root_certificates = Some(enabled);
+        self
+    }
+
+    /// Present a client certificate for mutual TLS
+    pub fn client_identity(mut self, identity: ClientIdentity) -> Self {
+        self.client_identity = Some(identity);
+        self
+    }
+
+    /// Pin a public key as `sha256/<base64>`
+    ///
+    /// Once a pin is set, connections fail unless a certificate in the
+    /// server's chain has a pinned key. Add a backup pin before rotating
+    /// keys. Requires the rustls backend.
+    pub fn pin_certificate(mut self, pin: impl Into<String>) -> Self {
+        self.certificate_pins.push(pin.into());
+        self
+    }
+
+    /// Send all requests through a proxy
+    ///
+    /// Supports `http://`, `https://`, `socks5://` and `socks5h://` URLs.
+    /// Replaces the system proxy settings.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy_url = Some(url.into());
+        self
+    }
+
+    /// Authenticate with the proxy
+    pub fn proxy_credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy_credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Bypass the proxy for a host, `.domain` suffix, IP address or CIDR block
+    pub fn no_proxy(mut self, host: impl Into<String>) -> Self {
+        self.no_proxy.push(host.into());
+        self
+    }
+
+    /// Honour the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment
+    /// variables when no proxy is set (default: true)
+    pub fn system_proxy(mut self, enabled: bool) -> Self {
+        self.system_proxy = Some(enabled);
+        self
+    }
+
+    /// Enable or disable automatic idempotency keys for POST and PATCH
+    ///
+    /// When disabled, non-idempotent requests are only retried if the caller
+    /// supplies its own key.
+    pub fn idempotency_keys(mut self, enabled: bool) -> Self {
+        self.idempotency_keys = Some(enabled);
+        self
+    }
+
+    /// Enable or disable pacing based on server rate-limit headers
+    ///
+    /// When enabled, requests are slowed down as the remaining quota reported
+    /// by the server approaches zero instead of running into a 429.
+    pub fn adaptive_throttling(mut self, enabled: bool) -> Self {
+        self.adaptive_throttling = Some(enabled);
+        self
+    }
+
+    /// Enable or disable W3C `traceparent`/`tracestate` headers
+    ///
+    /// Disabled by default, so requests carry no trace headers unless asked
+    /// to. Enable it with the `opentelemetry` feature to join the caller's
+    /// distributed traces.
+    pub fn propagate_trace_context(mut self, enabled: bool) -> Self {
+        self.propagate_trace_context = Some(enabled);
+        self
+    }
+
+    /// Authenticate clients created from this configuration
+    pub fn credentials(mut self, credentials: CredentialSettings) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> crate::Result<Config> {
         let base_url = self.base_url
@@ -297,9 +959,46 @@ impl ConfigBuilder {
             ))?;
 
         let default_config = Config::default();
-        
+
+        let proxy = match self.proxy_url {
+            Some(url) => Some(ProxySettings {
+                url: Url::parse(&url).map_err(|e| crate::Error::config_with_hint(
+                    format!("Invalid proxy URL: {}", e),
+                    "Use a URL like http://proxy.internal:3128",
+                ))?,
+                credentials: self.proxy_credentials,
+                no_proxy: self.no_proxy,
+            }),
+            None if self.proxy_credentials.is_some() || !self.no_proxy.is_empty() => {
+                return Err(crate::Error::config_with_hint(
+                    "Proxy credentials and no_proxy hosts require a proxy",
+                    "Set the proxy URL with .proxy(\"http://proxy.internal:3128\")",
+                ));
+            }
+            None => default_config.proxy,
+        };
+
+        let failover_endpoints = self
+            .failover_endpoints
+            .into_iter()
+            .map(|(name, url)| {
+                let url = Url::parse(&url).map_err(|e| crate::Error::config_with_hint(
+                    format!("Invalid URL for endpoint {}: {}", name, e),
+                    "Use a URL like https://eu.api.stateset.io",
+                ))?;
+                Ok(Endpoint { name, url })
+            })
+            .collect::<crate::Result<_>>()?;
+
         let config = Config {
             base_url,
+            failover_endpoints,
+            failover: self.failover.unwrap_or(default_config.failover),
+            circuit_breaker: self.circuit_breaker.or(default_config.circuit_breaker),
+            api_version: self.api_version.unwrap_or(default_config.api_version),
+            api_versions: self.api_versions,
+            api_prefix: self.api_prefix.or(default_config.api_prefix),
+            api_version_header: self.api_version_header.or(default_config.api_version_header),
             timeout: self.timeout.unwrap_or(default_config.timeout),
             connect_timeout: self.connect_timeout.unwrap_or(default_config.connect_timeout),
             retry_attempts: self.retry_attempts.unwrap_or(default_config.retry_attempts),
@@ -307,13 +1006,36 @@ impl ConfigBuilder {
             max_retry_delay: self.max_retry_delay.unwrap_or(default_config.max_retry_delay),
             retry_multiplier: self.retry_multiplier.unwrap_or(default_config.retry_multiplier),
             rate_limit: self.rate_limit.or(default_config.rate_limit),
+            rate_limit_groups: self.rate_limit_groups.unwrap_or(default_config.rate_limit_groups),
             user_agent: self.user_agent.unwrap_or(default_config.user_agent),
             pool_settings: self.pool_settings.unwrap_or(default_config.pool_settings),
+            background_concurrency: self.background_concurrency.or(default_config.background_concurrency),
             compression: self.compression.unwrap_or(default_config.compression),
+            request_compression_threshold: self
+                .request_compression_threshold
+                .or(default_config.request_compression_threshold),
+            request_compression_exempt: self
+                .request_compression_exempt
+                .unwrap_or(default_config.request_compression_exempt),
             keep_alive: self.keep_alive.or(default_config.keep_alive),
             max_redirects: self.max_redirects.unwrap_or(default_config.max_redirects),
             default_headers: self.default_headers.unwrap_or(default_config.default_headers),
             tls_verification: self.tls_verification.unwrap_or(default_config.tls_verification),
+            tls_backend: self.tls_backend.or(default_config.tls_backend),
+            root_certificates: self.root_certificates,
+            built_in_root_certificates: self
+                .built_in_root_certificates
+                .unwrap_or(default_config.built_in_root_certificates),
+            client_identity: self.client_identity.or(default_config.client_identity),
+            certificate_pins: self.certificate_pins,
+            proxy,
+            system_proxy: self.system_proxy.unwrap_or(default_config.system_proxy),
+            idempotency_keys: self.idempotency_keys.unwrap_or(default_config.idempotency_keys),
+            adaptive_throttling: self.adaptive_throttling.unwrap_or(default_config.adaptive_throttling),
+            propagate_trace_context: self
+                .propagate_trace_context
+                .unwrap_or(default_config.propagate_trace_context),
+            credentials: self.credentials.or(default_config.credentials),
         };
 
         config.validate()?;
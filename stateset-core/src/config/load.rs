@@ -0,0 +1,797 @@
+//! Loading configuration from files and environment variables
+//!
+//! Every source is a layer applied on top of a [`ConfigBuilder`]: values a
+//! layer sets replace earlier ones, lists and maps are extended, and builder
+//! calls made afterwards override everything loaded. [`Config::load`] applies
+//! the standard chain, lowest precedence first:
+//!
+//! 1. built-in defaults;
+//! 2. the top level of the file named by `STATESET_CONFIG`;
+//! 3. the profile named by `STATESET_PROFILE`, or the file's `default_profile`;
+//! 4. `STATESET_*` environment variables.
+//!
+//! Files are TOML or JSON, chosen by extension, and use the names of the
+//! [`ConfigBuilder`] methods as keys. Durations are whole seconds or strings
+//! such as `"500ms"`, `"30s"`, `"2m"` and `"1h"`; certificate paths are
+//! relative to the file.
+//!
+//! ```toml
+//! base_url = "https://api.stateset.io"
+//! timeout = "30s"
+//! retry_attempts = 3
+//! default_profile = "production"
+//!
+//! [pool_settings]
+//! max_total_connections = 64
+//!
+//! [default_headers]
+//! X-Team = "fulfillment"
+//!
+//! [profiles.sandbox]
+//! base_url = "https://sandbox.api.stateset.io"
+//! credentials = { api_key = "sk_test_..." }
+//!
+//! [profiles.production.credentials]
+//! client_id = "orders-service"
+//! client_secret = "..."
+//! ```
+
+use super::{Certificate, ClientIdentity, Config, ConfigBuilder, CredentialSettings, TlsBackend};
+use crate::{Error, Result};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+
+/// Prefix of the environment variables read by [`ConfigBuilder::merge_env`]
+const ENV_PREFIX: &str = "STATESET_";
+
+/// One layer of settings: the top level of a file, a profile or the environment
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Settings {
+    base_url: Option<String>,
+    #[serde(default)]
+    failover_endpoints: Vec<EndpointLayer>,
+    #[serde(default)]
+    failover: FailoverLayer,
+    #[serde(default)]
+    circuit_breaker: CircuitBreakerLayer,
+    api_version: Option<String>,
+    #[serde(default)]
+    api_versions: HashMap<String, String>,
+    api_prefix: Option<String>,
+    api_version_header: Option<String>,
+    #[serde(default, deserialize_with = "duration")]
+    timeout: Option<Duration>,
+    #[serde(default, deserialize_with = "duration")]
+    connect_timeout: Option<Duration>,
+    retry_attempts: Option<u32>,
+    #[serde(default, deserialize_with = "duration")]
+    retry_delay: Option<Duration>,
+    #[serde(default, deserialize_with = "duration")]
+    max_retry_delay: Option<Duration>,
+    retry_multiplier: Option<f64>,
+    rate_limit: Option<RateLimit>,
+    #[serde(default)]
+    rate_limit_groups: HashMap<String, RateLimit>,
+    user_agent: Option<String>,
+    #[serde(default)]
+    pool_settings: PoolLayer,
+    background_concurrency: Option<usize>,
+    compression: Option<bool>,
+    request_compression_threshold: Option<usize>,
+    #[serde(default)]
+    request_compression_exempt: Vec<String>,
+    #[serde(default, deserialize_with = "duration")]
+    keep_alive: Option<Duration>,
+    max_redirects: Option<u32>,
+    #[serde(default)]
+    default_headers: HashMap<String, String>,
+    tls_verification: Option<bool>,
+    tls_backend: Option<Backend>,
+    #[serde(default)]
+    root_certificates: Vec<PathBuf>,
+    built_in_root_certificates: Option<bool>,
+    client_certificate: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    client_pkcs12: Option<PathBuf>,
+    client_pkcs12_password: Option<String>,
+    #[serde(default)]
+    certificate_pins: Vec<String>,
+    proxy: Option<String>,
+    proxy_username: Option<String>,
+    proxy_password: Option<String>,
+    #[serde(default)]
+    no_proxy: Vec<String>,
+    system_proxy: Option<bool>,
+    idempotency_keys: Option<bool>,
+    adaptive_throttling: Option<bool>,
+    propagate_trace_context: Option<bool>,
+    #[serde(default)]
+    credentials: CredentialLayer,
+    /// Only allowed at the top level of a file
+    default_profile: Option<String>,
+    /// Only allowed at the top level of a file
+    #[serde(default)]
+    profiles: HashMap<String, Settings>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EndpointLayer {
+    name: String,
+    url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FailoverLayer {
+    failure_threshold: Option<u32>,
+    #[serde(default, deserialize_with = "duration")]
+    cool_down: Option<Duration>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CircuitBreakerLayer {
+    failure_threshold: Option<u32>,
+    #[serde(default, deserialize_with = "duration")]
+    recovery_timeout: Option<Duration>,
+    half_open_probes: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PoolLayer {
+    max_connections_per_host: Option<usize>,
+    max_total_connections: Option<usize>,
+    #[serde(default, deserialize_with = "duration")]
+    idle_timeout: Option<Duration>,
+    #[serde(default, deserialize_with = "duration")]
+    keep_alive_timeout: Option<Duration>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CredentialLayer {
+    bearer_token: Option<String>,
+    api_key: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+}
+
+impl std::fmt::Debug for CredentialLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CredentialLayer")
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimit {
+    requests: u32,
+    #[serde(deserialize_with = "required_duration")]
+    per: Duration,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Backend {
+    Rustls,
+    NativeTls,
+}
+
+impl From<Backend> for TlsBackend {
+    fn from(backend: Backend) -> Self {
+        match backend {
+            Backend::Rustls => TlsBackend::Rustls,
+            Backend::NativeTls => TlsBackend::NativeTls,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDuration {
+    Seconds(u64),
+    Text(String),
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<Duration>, D::Error> {
+    required_duration(deserializer).map(Some)
+}
+
+fn required_duration<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Duration, D::Error> {
+    match RawDuration::deserialize(deserializer)? {
+        RawDuration::Seconds(seconds) => Ok(Duration::from_secs(seconds)),
+        RawDuration::Text(text) => parse_duration(&text).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid duration {:?}, expected e.g. \"30s\"", text))
+        }),
+    }
+}
+
+/// Parse `500ms`, `30s`, `2m`, `1h` or a whole number of seconds
+fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+fn invalid_file(path: &Path, error: impl std::fmt::Display) -> Error {
+    Error::config_with_hint(
+        format!("Invalid config file {}: {}", path.display(), error),
+        "Keys use the ConfigBuilder method names, e.g. timeout = \"30s\"",
+    )
+}
+
+fn invalid_var(name: &str, value: &str, hint: &str) -> Error {
+    Error::config_with_hint(format!("Invalid {}: {:?}", name, value), hint.to_string())
+}
+
+impl Settings {
+    /// Read a TOML or JSON file
+    fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            Error::config_with_hint(
+                format!("Failed to read config file {}: {}", path.display(), e),
+                "Check the path in STATESET_CONFIG",
+            )
+        })?;
+
+        let settings: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(|e| invalid_file(path, e))?,
+            Some("json") => serde_json::from_str(&text).map_err(|e| invalid_file(path, e))?,
+            _ => {
+                return Err(Error::config_with_hint(
+                    format!("Unsupported config file format: {}", path.display()),
+                    "Use a .toml or .json file",
+                ))
+            }
+        };
+
+        if let Some((name, _)) = settings
+            .profiles
+            .iter()
+            .find(|(_, profile)| profile.default_profile.is_some() || !profile.profiles.is_empty())
+        {
+            return Err(invalid_file(path, format!("profile {:?} cannot define profiles", name)));
+        }
+        Ok(settings)
+    }
+
+    /// Read the `STATESET_*` variables among `vars`
+    fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        const DURATION: &str = "Use a duration like 30s, 500ms or 2m";
+        const NUMBER: &str = "Use a whole number";
+        const BOOL: &str = "Use true or false";
+        const RATE: &str = "Use requests/duration, e.g. 100/60s";
+
+        fn parse<T: std::str::FromStr>(name: &str, value: &str, hint: &str) -> Result<T> {
+            value.trim().parse().map_err(|_| invalid_var(name, value, hint))
+        }
+        fn duration(name: &str, value: &str) -> Result<Duration> {
+            parse_duration(value).ok_or_else(|| invalid_var(name, value, DURATION))
+        }
+        fn list(value: &str) -> impl Iterator<Item = String> + '_ {
+            value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(String::from)
+        }
+        fn rate_limit(name: &str, value: &str) -> Result<RateLimit> {
+            value
+                .split_once('/')
+                .and_then(|(requests, per)| {
+                    Some(RateLimit {
+                        requests: requests.trim().parse().ok()?,
+                        per: parse_duration(per)?,
+                    })
+                })
+                .ok_or_else(|| invalid_var(name, value, RATE))
+        }
+
+        let mut settings = Self::default();
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            match key {
+                "BASE_URL" => settings.base_url = Some(value),
+                "FAILOVER_ENDPOINTS" => {
+                    for endpoint in list(&value) {
+                        let (name, url) = endpoint.split_once('=').ok_or_else(|| {
+                            invalid_var(&name, &value, "Use name=url pairs, e.g. eu=https://eu.api.stateset.io")
+                        })?;
+                        settings.failover_endpoints.push(EndpointLayer {
+                            name: name.trim().to_string(),
+                            url: url.trim().to_string(),
+                        });
+                    }
+                }
+                "FAILOVER_THRESHOLD" => settings.failover.failure_threshold = Some(parse(&name, &value, NUMBER)?),
+                "FAILOVER_COOL_DOWN" => settings.failover.cool_down = Some(duration(&name, &value)?),
+                "CIRCUIT_BREAKER_THRESHOLD" => {
+                    settings.circuit_breaker.failure_threshold = Some(parse(&name, &value, NUMBER)?)
+                }
+                "CIRCUIT_BREAKER_RECOVERY_TIMEOUT" => {
+                    settings.circuit_breaker.recovery_timeout = Some(duration(&name, &value)?)
+                }
+                "CIRCUIT_BREAKER_HALF_OPEN_PROBES" => {
+                    settings.circuit_breaker.half_open_probes = Some(parse(&name, &value, NUMBER)?)
+                }
+                "API_VERSION" => settings.api_version = Some(value),
+                "API_PREFIX" => settings.api_prefix = Some(value),
+                "API_VERSION_HEADER" => settings.api_version_header = Some(value),
+                "TIMEOUT" => settings.timeout = Some(duration(&name, &value)?),
+                "CONNECT_TIMEOUT" => settings.connect_timeout = Some(duration(&name, &value)?),
+                "RETRY_ATTEMPTS" => settings.retry_attempts = Some(parse(&name, &value, NUMBER)?),
+                "RETRY_DELAY" => settings.retry_delay = Some(duration(&name, &value)?),
+                "MAX_RETRY_DELAY" => settings.max_retry_delay = Some(duration(&name, &value)?),
+                "RETRY_MULTIPLIER" => {
+                    settings.retry_multiplier = Some(parse(&name, &value, "Use a number like 2.0")?)
+                }
+                "RATE_LIMIT" => settings.rate_limit = Some(rate_limit(&name, &value)?),
+                "USER_AGENT" => settings.user_agent = Some(value),
+                "MAX_CONNECTIONS_PER_HOST" => {
+                    settings.pool_settings.max_connections_per_host = Some(parse(&name, &value, NUMBER)?)
+                }
+                "MAX_TOTAL_CONNECTIONS" => {
+                    settings.pool_settings.max_total_connections = Some(parse(&name, &value, NUMBER)?)
+                }
+                "POOL_IDLE_TIMEOUT" => settings.pool_settings.idle_timeout = Some(duration(&name, &value)?),
+                "POOL_KEEP_ALIVE_TIMEOUT" => {
+                    settings.pool_settings.keep_alive_timeout = Some(duration(&name, &value)?)
+                }
+                "BACKGROUND_CONCURRENCY" => settings.background_concurrency = Some(parse(&name, &value, NUMBER)?),
+                "COMPRESSION" => settings.compression = Some(parse(&name, &value, BOOL)?),
+                "REQUEST_COMPRESSION_THRESHOLD" => {
+                    settings.request_compression_threshold = Some(parse(&name, &value, NUMBER)?)
+                }
+                "REQUEST_COMPRESSION_EXEMPT" => settings.request_compression_exempt.extend(list(&value)),
+                "KEEP_ALIVE" => settings.keep_alive = Some(duration(&name, &value)?),
+                "MAX_REDIRECTS" => settings.max_redirects = Some(parse(&name, &value, NUMBER)?),
+                "TLS_VERIFICATION" => settings.tls_verification = Some(parse(&name, &value, BOOL)?),
+                "TLS_BACKEND" => {
+                    settings.tls_backend = Some(match value.trim() {
+                        "rustls" => Backend::Rustls,
+                        "native-tls" => Backend::NativeTls,
+                        _ => return Err(invalid_var(&name, &value, "Use rustls or native-tls")),
+                    })
+                }
+                "ROOT_CERTIFICATES" => settings.root_certificates.extend(list(&value).map(PathBuf::from)),
+                "BUILT_IN_ROOT_CERTIFICATES" => {
+                    settings.built_in_root_certificates = Some(parse(&name, &value, BOOL)?)
+                }
+                "CLIENT_CERTIFICATE" => settings.client_certificate = Some(value.into()),
+                "CLIENT_KEY" => settings.client_key = Some(value.into()),
+                "CLIENT_PKCS12" => settings.client_pkcs12 = Some(value.into()),
+                "CLIENT_PKCS12_PASSWORD" => settings.client_pkcs12_password = Some(value),
+                "CERTIFICATE_PINS" => settings.certificate_pins.extend(list(&value)),
+                "PROXY" => settings.proxy = Some(value),
+                "PROXY_USERNAME" => settings.proxy_username = Some(value),
+                "PROXY_PASSWORD" => settings.proxy_password = Some(value),
+                "NO_PROXY" => settings.no_proxy.extend(list(&value)),
+                "SYSTEM_PROXY" => settings.system_proxy = Some(parse(&name, &value, BOOL)?),
+                "IDEMPOTENCY_KEYS" => settings.idempotency_keys = Some(parse(&name, &value, BOOL)?),
+                "ADAPTIVE_THROTTLING" => settings.adaptive_throttling = Some(parse(&name, &value, BOOL)?),
+                "PROPAGATE_TRACE_CONTEXT" => {
+                    settings.propagate_trace_context = Some(parse(&name, &value, BOOL)?)
+                }
+                "TOKEN" => settings.credentials.bearer_token = Some(value),
+                "API_KEY" => settings.credentials.api_key = Some(value),
+                "CLIENT_ID" => settings.credentials.client_id = Some(value),
+                "CLIENT_SECRET" => settings.credentials.client_secret = Some(value),
+                _ => {
+                    if let Some(header) = key.strip_prefix("HEADER_") {
+                        settings.default_headers.insert(header.replace('_', "-"), value);
+                    } else if let Some(group) = key.strip_prefix("RATE_LIMIT_") {
+                        let limit = rate_limit(&name, &value)?;
+                        settings.rate_limit_groups.insert(group.to_lowercase(), limit);
+                    } else if let Some(resource) = key.strip_prefix("API_VERSION_") {
+                        settings.api_versions.insert(resource.to_lowercase().replace('_', "-"), value);
+                    }
+                    // Anything else, e.g. STATESET_CONFIG, is not a setting
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Apply this layer to a builder, resolving paths against `base_dir`
+    fn apply(self, mut builder: ConfigBuilder, base_dir: &Path) -> Result<ConfigBuilder> {
+        if let Some(url) = self.base_url {
+            builder.base_url = Some(Url::parse(&url).map_err(|e| {
+                Error::config_with_hint(
+                    format!("Invalid base URL {:?}: {}", url, e),
+                    "Ensure the URL starts with http:// or https://",
+                )
+            })?);
+        }
+        for endpoint in self.failover_endpoints {
+            builder = builder.failover_endpoint(endpoint.name, endpoint.url);
+        }
+        if self.failover.failure_threshold.is_some() || self.failover.cool_down.is_some() {
+            let mut settings = builder.failover.take().unwrap_or_default();
+            settings.failure_threshold = self.failover.failure_threshold.unwrap_or(settings.failure_threshold);
+            settings.cool_down = self.failover.cool_down.unwrap_or(settings.cool_down);
+            builder.failover = Some(settings);
+        }
+        let breaker = self.circuit_breaker;
+        if breaker.failure_threshold.is_some() || breaker.recovery_timeout.is_some() || breaker.half_open_probes.is_some() {
+            let mut settings = builder.circuit_breaker.take().unwrap_or_default();
+            settings.failure_threshold = breaker.failure_threshold.unwrap_or(settings.failure_threshold);
+            settings.recovery_timeout = breaker.recovery_timeout.unwrap_or(settings.recovery_timeout);
+            settings.half_open_probes = breaker.half_open_probes.unwrap_or(settings.half_open_probes);
+            builder.circuit_breaker = Some(settings);
+        }
+        builder.api_version = self.api_version.or(builder.api_version);
+        for (resource, version) in self.api_versions {
+            builder = builder.resource_api_version(resource, version);
+        }
+        if let Some(prefix) = self.api_prefix {
+            builder = builder.api_prefix(prefix);
+        }
+        builder.api_version_header = self.api_version_header.or(builder.api_version_header);
+        builder.timeout = self.timeout.or(builder.timeout);
+        builder.connect_timeout = self.connect_timeout.or(builder.connect_timeout);
+        builder.retry_attempts = self.retry_attempts.or(builder.retry_attempts);
+        builder.retry_delay = self.retry_delay.or(builder.retry_delay);
+        builder.max_retry_delay = self.max_retry_delay.or(builder.max_retry_delay);
+        builder.retry_multiplier = self.retry_multiplier.or(builder.retry_multiplier);
+        builder.rate_limit = self.rate_limit.map(|limit| (limit.requests, limit.per)).or(builder.rate_limit);
+        for (group, limit) in self.rate_limit_groups {
+            builder = builder.rate_limit_group(group, limit.requests, limit.per);
+        }
+        builder.user_agent = self.user_agent.or(builder.user_agent);
+
+        let pool = self.pool_settings;
+        if pool.max_connections_per_host.is_some()
+            || pool.max_total_connections.is_some()
+            || pool.idle_timeout.is_some()
+            || pool.keep_alive_timeout.is_some()
+        {
+            let mut settings = builder.pool_settings.take().unwrap_or_default();
+            settings.max_connections_per_host =
+                pool.max_connections_per_host.unwrap_or(settings.max_connections_per_host);
+            settings.max_total_connections = pool.max_total_connections.unwrap_or(settings.max_total_connections);
+            settings.idle_timeout = pool.idle_timeout.unwrap_or(settings.idle_timeout);
+            settings.keep_alive_timeout = pool.keep_alive_timeout.unwrap_or(settings.keep_alive_timeout);
+            builder.pool_settings = Some(settings);
+        }
+
+        builder.background_concurrency = self.background_concurrency.or(builder.background_concurrency);
+        builder.compression = self.compression.or(builder.compression);
+        builder.request_compression_threshold =
+            self.request_compression_threshold.or(builder.request_compression_threshold);
+        for group in self.request_compression_exempt {
+            builder = builder.request_compression_exempt(group);
+        }
+        builder.keep_alive = self.keep_alive.or(builder.keep_alive);
+        builder.max_redirects = self.max_redirects.or(builder.max_redirects);
+        for (name, value) in self.default_headers {
+            builder = builder.default_header(name, value);
+        }
+
+        builder.tls_verification = self.tls_verification.or(builder.tls_verification);
+        builder.tls_backend = self.tls_backend.map(TlsBackend::from).or(builder.tls_backend);
+        for path in self.root_certificates {
+            let path = base_dir.join(path);
+            let certificate = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("der") => Certificate::from_der_file(&path)?,
+                _ => Certificate::from_pem_file(&path)?,
+            };
+            builder.root_certificates.push(certificate);
+        }
+        builder.built_in_root_certificates = self.built_in_root_certificates.or(builder.built_in_root_certificates);
+        let identity = match (self.client_certificate, self.client_key, self.client_pkcs12) {
+            (None, None, None) => None,
+            (Some(certificate), Some(key), None) => Some(ClientIdentity::from_pem_files(
+                base_dir.join(certificate),
+                base_dir.join(key),
+            )?),
+            (None, None, Some(archive)) => Some(ClientIdentity::from_pkcs12_file(
+                base_dir.join(archive),
+                self.client_pkcs12_password.unwrap_or_default(),
+            )?),
+            _ => {
+                return Err(Error::config_with_hint(
+                    "Incomplete or conflicting client identity",
+                    "Set client_certificate and client_key, or client_pkcs12",
+                ))
+            }
+        };
+        builder.client_identity = identity.or(builder.client_identity);
+        builder.certificate_pins.extend(self.certificate_pins);
+
+        builder.proxy_url = self.proxy.or(builder.proxy_url);
+        match (self.proxy_username, self.proxy_password) {
+            (Some(username), Some(password)) => builder.proxy_credentials = Some((username, password)),
+            (None, None) => {}
+            _ => {
+                return Err(Error::config_with_hint(
+                    "Incomplete proxy credentials",
+                    "Set both proxy_username and proxy_password",
+                ))
+            }
+        }
+        builder.no_proxy.extend(self.no_proxy);
+        builder.system_proxy = self.system_proxy.or(builder.system_proxy);
+        builder.idempotency_keys = self.idempotency_keys.or(builder.idempotency_keys);
+        builder.adaptive_throttling = self.adaptive_throttling.or(builder.adaptive_throttling);
+        builder.propagate_trace_context = self.propagate_trace_context.or(builder.propagate_trace_context);
+        builder.credentials = self.credentials.into_settings()?.or(builder.credentials);
+
+        Ok(builder)
+    }
+}
+
+impl CredentialLayer {
+    fn into_settings(self) -> Result<Option<CredentialSettings>> {
+        match self {
+            Self { bearer_token: None, api_key: None, client_id: None, client_secret: None } => Ok(None),
+            Self { bearer_token: Some(token), api_key: None, client_id: None, client_secret: None } => {
+                Ok(Some(CredentialSettings::Bearer(token)))
+            }
+            Self { bearer_token: None, api_key: Some(key), client_id: None, client_secret: None } => {
+                Ok(Some(CredentialSettings::ApiKey(key)))
+            }
+            Self { bearer_token: None, api_key: None, client_id: Some(client_id), client_secret: Some(client_secret) } => {
+                Ok(Some(CredentialSettings::OAuth2 { client_id, client_secret }))
+            }
+            _ => Err(Error::config_with_hint(
+                "Incomplete or conflicting credentials",
+                "Set one of a bearer token, an API key, or a client ID with its client secret",
+            )),
+        }
+    }
+}
+
+impl ConfigBuilder {
+    /// Apply a TOML or JSON configuration file and its `default_profile`
+    pub fn merge_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let settings = Settings::read(path)?;
+        let profile = settings.default_profile.clone();
+        self.merge_settings(path, settings, profile.as_deref())
+    }
+
+    /// Apply the top level of a configuration file, then one of its profiles
+    pub fn merge_profile(self, path: impl AsRef<Path>, profile: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let settings = Settings::read(path)?;
+        self.merge_settings(path, settings, Some(profile))
+    }
+
+    fn merge_settings(self, path: &Path, mut settings: Settings, profile: Option<&str>) -> Result<Self> {
+        let profile = match profile {
+            Some(name) => Some(settings.profiles.remove(name).ok_or_else(|| {
+                let mut available: Vec<_> = settings.profiles.keys().map(String::as_str).collect();
+                available.sort_unstable();
+                Error::config_with_hint(
+                    format!("Unknown profile {:?} in {}", name, path.display()),
+                    format!("Available profiles: {}", available.join(", ")),
+                )
+            })?),
+            None => None,
+        };
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        let builder = settings.apply(self, base_dir)?;
+        match profile {
+            Some(profile) => profile.apply(builder, base_dir),
+            None => Ok(builder),
+        }
+    }
+
+    /// Apply the `STATESET_*` environment variables
+    ///
+    /// Credentials come from `STATESET_TOKEN`, `STATESET_API_KEY` or
+    /// `STATESET_CLIENT_ID` with `STATESET_CLIENT_SECRET`; default headers
+    /// from `STATESET_HEADER_<NAME>`, group rate limits from
+    /// `STATESET_RATE_LIMIT_<GROUP>=requests/duration` and pinned resource
+    /// versions from `STATESET_API_VERSION_<RESOURCE>`, with underscores in
+    /// the resource name read as hyphens (`WORK_ORDERS` is `work-orders`).
+    /// Other settings use
+    /// the upper-cased builder method name, e.g. `STATESET_RETRY_ATTEMPTS`.
+    /// Lists such as `STATESET_NO_PROXY` are comma-separated, and relative
+    /// paths resolve against the working directory.
+    pub fn merge_env(self) -> Result<Self> {
+        Settings::from_vars(std::env::vars())?.apply(self, Path::new(""))
+    }
+
+    fn require_base_url(self) -> Result<Self> {
+        if self.base_url.is_none() {
+            return Err(Error::config_with_hint(
+                "Base URL is required",
+                "Set base_url in the config file or STATESET_BASE_URL",
+            ));
+        }
+        Ok(self)
+    }
+}
+
+impl Config {
+    /// Load the configuration from `STATESET_*` environment variables
+    ///
+    /// See [`ConfigBuilder::merge_env`] for the variable names.
+    pub fn from_env() -> Result<Self> {
+        Self::builder().merge_env()?.require_base_url()?.build()
+    }
+
+    /// Load a TOML or JSON configuration file, applying its `default_profile`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::builder().merge_file(path)?.require_base_url()?.build()
+    }
+
+    /// Load a named profile of a TOML or JSON configuration file
+    pub fn from_profile(path: impl AsRef<Path>, profile: &str) -> Result<Self> {
+        Self::builder().merge_profile(path, profile)?.require_base_url()?.build()
+    }
+
+    /// Load the file named by `STATESET_CONFIG`, if set, with the profile
+    /// named by `STATESET_PROFILE`, then apply the environment variables
+    pub fn load() -> Result<Self> {
+        let profile = std::env::var("STATESET_PROFILE").ok();
+        let builder = match (std::env::var_os("STATESET_CONFIG"), profile) {
+            (Some(path), Some(profile)) => Self::builder().merge_profile(path, &profile)?,
+            (Some(path), None) => Self::builder().merge_file(path)?,
+            (None, Some(_)) => {
+                return Err(Error::config_with_hint(
+                    "STATESET_PROFILE is set without STATESET_CONFIG",
+                    "Point STATESET_CONFIG at the file that defines the profile",
+                ))
+            }
+            (None, None) => Self::builder(),
+        };
+        builder.merge_env()?.require_base_url()?.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+base_url = "https://api.stateset.io"
+timeout = "20s"
+retry_attempts = 5
+retry_delay = 2
+default_profile = "production"
+
+[pool_settings]
+max_total_connections = 8
+
+[default_headers]
+X-Team = "fulfillment"
+
+[rate_limit_groups.analytics]
+requests = 10
+per = "1m"
+
+[profiles.sandbox]
+base_url = "https://sandbox.api.stateset.io"
+retry_attempts = 1
+credentials = { api_key = "sk_test" }
+
+[profiles.production.credentials]
+client_id = "orders"
+client_secret = "secret"
+"#;
+
+    fn write(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_profile_overrides_file_and_env_overrides_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "stateset.toml", FILE);
+
+        let builder = Config::builder().merge_profile(&path, "sandbox").unwrap();
+        let env = Settings::from_vars(vars(&[
+            ("STATESET_RETRY_ATTEMPTS", "2"),
+            ("STATESET_HEADER_X_REQUEST_SOURCE", "batch"),
+            ("STATESET_RATE_LIMIT_ORDERS", "50/30s"),
+            ("STATESET_FAILOVER_ENDPOINTS", "eu=https://eu.api.stateset.io"),
+            ("STATESET_FAILOVER_COOL_DOWN", "10s"),
+            ("STATESET_CIRCUIT_BREAKER_HALF_OPEN_PROBES", "2"),
+            ("STATESET_API_VERSION", "v2"),
+            ("STATESET_API_VERSION_HEADER", "StateSet-Version"),
+            ("STATESET_API_VERSION_WORK_ORDERS", "v1"),
+            ("STATESET_API_PREFIX", "gateway/stateset/"),
+            ("PATH", "/usr/bin"),
+        ]))
+        .unwrap();
+        let config = env.apply(builder, Path::new("")).unwrap().build().unwrap();
+
+        assert_eq!(config.base_url.as_str(), "https://sandbox.api.stateset.io/");
+        assert_eq!(config.timeout, Duration::from_secs(20));
+        assert_eq!(config.retry_delay, Duration::from_secs(2));
+        assert_eq!(config.retry_attempts, 2);
+        assert_eq!(config.pool_settings.max_total_connections, 8);
+        assert_eq!(config.pool_settings.max_connections_per_host, 10);
+        assert_eq!(config.default_headers["X-Team"], "fulfillment");
+        assert_eq!(config.default_headers["X-REQUEST-SOURCE"], "batch");
+        assert_eq!(config.rate_limit_groups["analytics"], (10, Duration::from_secs(60)));
+        assert_eq!(config.rate_limit_groups["orders"], (50, Duration::from_secs(30)));
+        assert_eq!(config.credentials, Some(CredentialSettings::ApiKey("sk_test".to_string())));
+        assert_eq!(config.failover_endpoints[0].name, "eu");
+        assert_eq!(config.failover.cool_down, Duration::from_secs(10));
+        assert_eq!(config.failover.failure_threshold, 3);
+        let breaker = config.circuit_breaker.unwrap();
+        assert_eq!(breaker.half_open_probes, 2);
+        assert_eq!(breaker.failure_threshold, 5);
+        assert_eq!(config.api_version, "v2");
+        assert_eq!(config.api_versions["work-orders"], "v1");
+        assert_eq!(config.api_prefix.as_deref(), Some("/gateway/stateset"));
+        assert_eq!(config.api_version_header.as_deref(), Some("StateSet-Version"));
+    }
+
+    #[test]
+    fn test_default_profile_and_json_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::from_file(write(&dir, "stateset.toml", FILE)).unwrap();
+        assert_eq!(config.base_url.as_str(), "https://api.stateset.io/");
+        assert!(matches!(config.credentials, Some(CredentialSettings::OAuth2 { .. })));
+
+        let json = write(
+            &dir,
+            "stateset.json",
+            r#"{"base_url": "https://api.stateset.io", "keep_alive": "500ms", "tls_backend": "native-tls"}"#,
+        );
+        let config = Config::from_file(json).unwrap();
+        assert_eq!(config.keep_alive, Some(Duration::from_millis(500)));
+        assert_eq!(config.tls_backend, Some(TlsBackend::NativeTls));
+    }
+
+    #[test]
+    fn test_invalid_settings_are_configuration_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(&dir, "stateset.toml", FILE);
+
+        let error = Config::from_profile(&path, "staging").unwrap_err();
+        assert!(matches!(error, Error::Configuration { .. }));
+        assert!(error.to_string().contains("staging"));
+
+        let typo = write(&dir, "typo.toml", "base_url = \"https://api.stateset.io\"\ntimeuot = \"5s\"\n");
+        assert!(matches!(Config::from_file(typo), Err(Error::Configuration { .. })));
+
+        let bad_duration = write(&dir, "bad.toml", "timeout = \"soon\"\n");
+        assert!(Config::from_file(bad_duration).is_err());
+
+        let no_url = write(&dir, "no_url.toml", "retry_attempts = 1\n");
+        assert!(Config::from_file(no_url).is_err());
+
+        assert!(Settings::from_vars(vars(&[("STATESET_TIMEOUT", "fast")])).is_err());
+        assert!(Settings::from_vars(vars(&[("STATESET_COMPRESSION", "yes")])).is_err());
+
+        let conflicting = Settings::from_vars(vars(&[("STATESET_TOKEN", "t"), ("STATESET_API_KEY", "k")])).unwrap();
+        assert!(conflicting.apply(Config::builder(), Path::new("")).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("45"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("5 days"), None);
+    }
+}
@@ -48,6 +48,8 @@ pub enum Error {
         message: String,
         is_timeout: bool,
         can_retry: bool,
+        /// Whether the request may have reached the server
+        request_sent: bool,
     },
 
     /// Serialization/deserialization error
@@ -82,6 +84,8 @@ pub enum Error {
         attempts: u32,
         operation: String,
         last_error: Box<Error>,
+        /// Idempotency key sent with every attempt, for reconciliation
+        idempotency_key: Option<String>,
     },
 
     /// Connection pool error
@@ -102,6 +106,17 @@ pub enum Error {
         retry_after: Option<Duration>,
     },
 
+    /// Request rejected by an open client-side circuit breaker
+    ///
+    /// The request never left the client. It is not retryable: waiting out
+    /// `retry_after` inside a retry loop would defeat failing fast.
+    #[error("Circuit breaker {circuit} is open: {message}")]
+    CircuitOpen {
+        circuit: String,
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
     /// Invalid request format
     #[error("Invalid request: {message}")]
     InvalidRequest { 
@@ -195,6 +210,20 @@ impl Error {
             message: message.into(),
             is_timeout: false,
             can_retry: true,
+            request_sent: true,
+        }
+    }
+
+    /// Create a network error for a connection that was never established
+    ///
+    /// The request cannot have reached the server, so retrying it is safe
+    /// even for non-idempotent methods.
+    pub fn connection(message: impl Into<String>) -> Self {
+        Self::Network {
+            message: message.into(),
+            is_timeout: false,
+            can_retry: true,
+            request_sent: false,
         }
     }
 
@@ -230,12 +259,15 @@ impl Error {
     }
 
     /// Get the suggested retry delay
+    ///
+    /// Only set when the server or a circuit breaker named one; other
+    /// failures are left to the caller's backoff.
     pub fn retry_after(&self) -> Option<Duration> {
         match self {
             Self::RateLimit { retry_after, .. }
             | Self::Conflict { retry_after, .. }
-            | Self::ServiceUnavailable { retry_after, .. } => *retry_after,
-            Self::Network { .. } => Some(Duration::from_secs(1)),
+            | Self::ServiceUnavailable { retry_after, .. }
+            | Self::CircuitOpen { retry_after, .. } => *retry_after,
             _ => None,
         }
     }
@@ -256,6 +288,61 @@ impl Error {
         }
     }
 
+    /// Stable snake_case name of the variant, for metrics and trace labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "not_found",
+            Self::Authentication { .. } => "authentication",
+            Self::Authorization { .. } => "authorization",
+            Self::RateLimit { .. } => "rate_limit",
+            Self::Api { .. } => "api",
+            Self::Validation { .. } => "validation",
+            Self::Network { .. } => "network",
+            Self::Serialization { .. } => "serialization",
+            Self::Configuration { .. } => "configuration",
+            Self::Timeout { .. } => "timeout",
+            #[cfg(feature = "realtime")]
+            Self::WebSocket { .. } => "websocket",
+            Self::RetryExhausted { .. } => "retry_exhausted",
+            Self::ConnectionPool { .. } => "connection_pool",
+            Self::Conflict { .. } => "conflict",
+            Self::ServiceUnavailable { .. } => "service_unavailable",
+            Self::CircuitOpen { .. } => "circuit_open",
+            Self::InvalidRequest { .. } => "invalid_request",
+            Self::QuotaExceeded { .. } => "quota_exceeded",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// The error that ended the last attempt, unwrapping retry exhaustion
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Self::RetryExhausted { last_error, .. } => last_error.root_cause(),
+            _ => self,
+        }
+    }
+
+    /// Get the idempotency key the failed request was sent with, if any
+    pub fn idempotency_key(&self) -> Option<&str> {
+        match self {
+            Self::RetryExhausted { idempotency_key, .. } => idempotency_key.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Check whether the failed request may have reached the server
+    ///
+    /// Only connection failures and circuit breaker rejections are known not
+    /// to have been sent.
+    pub fn request_sent(&self) -> bool {
+        match self {
+            Self::Network { request_sent, .. } => *request_sent,
+            Self::CircuitOpen { .. } => false,
+            Self::RetryExhausted { last_error, .. } => last_error.request_sent(),
+            _ => true,
+        }
+    }
+
     /// Get the request ID if available
     pub fn request_id(&self) -> Option<&str> {
         match self {
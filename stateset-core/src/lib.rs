@@ -8,7 +8,10 @@ pub mod error;
 pub mod traits;
 pub mod types;
 
-pub use config::{Config, ConfigBuilder};
+pub use config::{
+    Certificate, CircuitBreakerSettings, ClientIdentity, Config, ConfigBuilder, CredentialSettings, Endpoint,
+    FailoverSettings, PoolSettings, ProxySettings, TlsBackend,
+};
 pub use error::{Error, Result};
 pub use traits::{ApiResource, Identifiable, ListableResource, Paginated};
 pub use types::{ResourceId, Timestamp, ListResponse}; 
\ No newline at end of file
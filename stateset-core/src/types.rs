@@ -5,10 +5,11 @@ use std::fmt;
 use std::str::FromStr;
 
 /// Reference type for inventory operations
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ReferenceType {
     /// Product reference
+    #[default]
     Product,
     /// Variant reference
     Variant,
@@ -20,12 +21,6 @@ pub enum ReferenceType {
     Custom(String),
 }
 
-impl Default for ReferenceType {
-    fn default() -> Self {
-        Self::Product
-    }
-}
-
 impl fmt::Display for ReferenceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -186,6 +186,12 @@ pub struct ReservationBuilder {
     metadata: Option<Metadata>,
 }
 
+impl Default for ReservationBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ReservationBuilder {
     pub fn new() -> Self {
         Self {
@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use stateset_core::{
     traits::{ApiResource, Identifiable},
-    types::{Address, Expandable, Metadata, Money, ResourceId, Timestamp},
+    types::{Expandable, Metadata, Money, ResourceId, Timestamp},
 };
 
 /// Product status enum
@@ -23,6 +23,7 @@ pub enum Channel {
 /// Real-time events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
+#[allow(clippy::large_enum_variant)]
 pub enum Event {
     Order(OrderEvent),
     Inventory(InventoryEvent),
@@ -15,7 +15,7 @@
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     // Initialize client
 //!     let client = Client::new("https://api.stateset.io")?
-//!         .authenticate(Credentials::bearer("your-api-token"))?;
+//!         .authenticate(Credentials::bearer("your-api-token"));
 //!
 //!     // Create an order
 //!     let order = client
@@ -35,9 +35,10 @@
 
 // Re-export core types
 pub use stateset_core::{
-    Config, ConfigBuilder, Error, Result,
+    Certificate, CircuitBreakerSettings, ClientIdentity, Config, ConfigBuilder, CredentialSettings, Endpoint,
+    Error, FailoverSettings, PoolSettings, ProxySettings, Result, TlsBackend,
     traits::{ApiResource, Identifiable, ListableResource, Paginated},
-    types::{Address, Contact, Money, ResourceId, Timestamp},
+    types::{Address, Contact, Money, ReferenceType, ResourceId, Timestamp},
 };
 
 // Re-export models
@@ -51,7 +52,21 @@ pub mod auth {
 }
 
 // Re-export the client
-pub use stateset_client::{Client, request::{ListOptions, SortOrder}};
+pub use stateset_client::{
+    Client,
+    circuit_breaker,
+    concurrency::Priority,
+    failover::EndpointHealth,
+    metrics::{Metrics, MetricsSnapshot},
+    request::{ListOptions, RequestOptions, SortOrder},
+    response::{ApiResponse, ResponseCapture, ResponseMetadata},
+};
+
+// Re-export the synchronous client if enabled
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    pub use stateset_client::blocking::*;
+}
 
 // Re-export real-time support if enabled
 #[cfg(feature = "realtime")]